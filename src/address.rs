@@ -0,0 +1,133 @@
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, net::IpAddr, str::FromStr};
+
+/// Address of a bridge.
+///
+/// The address is either an IP address, a hostname, a host with a port (e.g. `localhost:8080`) or a
+/// base URL (e.g. `http://hue.lan/proxy`).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Address {
+    https: bool,
+    host: String,
+    port: Option<u16>,
+    path: String,
+}
+
+impl Address {
+    /// Returns the base URL of the address, e.g. `http://hue.lan:8080/proxy`.
+    pub fn base_url(&self) -> String {
+        let scheme = if self.https { "https" } else { "http" };
+        let host = if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        };
+        match self.port {
+            Some(v) => format!("{}://{}:{}{}", scheme, host, v, self.path),
+            None => format!("{}://{}{}", scheme, host, self.path),
+        }
+    }
+}
+
+impl From<IpAddr> for Address {
+    fn from(v: IpAddr) -> Self {
+        Self {
+            https: false,
+            host: v.to_string(),
+            port: None,
+            path: String::new(),
+        }
+    }
+}
+
+impl FromStr for Address {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let invalid = || Error::Invalid(s.to_owned());
+        let (https, rest) = match s.find("://") {
+            Some(v) => match s[..v].to_lowercase().as_ref() {
+                "http" => (false, &s[v + 3..]),
+                "https" => (true, &s[v + 3..]),
+                _ => return Err(invalid()),
+            },
+            None => (false, s),
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(v) => (&rest[..v], rest[v..].trim_end_matches('/')),
+            None => (rest, ""),
+        };
+        let (host, port) = if let Some(v) = authority.strip_prefix('[') {
+            // IPv6 address in brackets, optionally followed by a port.
+            let end = v.find(']').ok_or_else(invalid)?;
+            match v[end + 1..].strip_prefix(':') {
+                Some(port) => (&v[..end], Some(port)),
+                None if v.len() == end + 1 => (&v[..end], None),
+                None => return Err(invalid()),
+            }
+        } else if authority.parse::<IpAddr>().is_ok() {
+            (authority, None)
+        } else {
+            match authority.rfind(':') {
+                Some(v) => (&authority[..v], Some(&authority[v + 1..])),
+                None => (authority, None),
+            }
+        };
+        if host.is_empty() {
+            return Err(invalid());
+        }
+        let port = match port {
+            Some(v) => Some(v.parse().map_err(|_| invalid())?),
+            None => None,
+        };
+        Ok(Self {
+            https,
+            host: host.to_owned(),
+            port,
+            path: path.to_owned(),
+        })
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        if self.https || !self.path.is_empty() {
+            write!(f, "{}", self.base_url())
+        } else if let Some(port) = self.port {
+            if self.host.contains(':') {
+                write!(f, "[{}]:{}", self.host, port)
+            } else {
+                write!(f, "{}:{}", self.host, port)
+            }
+        } else {
+            write!(f, "{}", self.host)
+        }
+    }
+}
+
+impl Serialize for Address {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Address {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(de::Error::custom)
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Invalid(String),
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Self::Invalid(v) => write!(f, "Invalid bridge address '{}'", v),
+        }
+    }
+}
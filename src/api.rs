@@ -0,0 +1,564 @@
+use crate::{address::Address, util};
+use huelib::{bridge::User, resource, response::Modified};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::{collections::HashMap, fmt, io::Read, time::Duration};
+
+/// Time to wait for a response of the bridge.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Sensor with every attribute that is sent by the bridge.
+///
+/// The sensor types of huelib only contain the state and config of presence sensors, so this type
+/// is used instead.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Sensor {
+    #[serde(skip)]
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+    #[serde(rename = "modelid")]
+    pub model_id: String,
+    #[serde(rename = "uniqueid")]
+    pub unique_id: Option<String>,
+    #[serde(rename = "manufacturername")]
+    pub manufacturer_name: Option<String>,
+    #[serde(rename = "swversion")]
+    pub software_version: String,
+    #[serde(default)]
+    pub state: SensorState,
+    #[serde(default)]
+    pub config: SensorConfig,
+    pub recycle: Option<bool>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct SensorState {
+    pub presence: Option<bool>,
+    pub flag: Option<bool>,
+    /// Temperature in hundredths of a degree Celsius.
+    pub temperature: Option<i32>,
+    /// Light level on a logarithmic scale, `10000 * log10(lux) + 1`.
+    #[serde(rename = "lightlevel")]
+    pub light_level: Option<u32>,
+    pub dark: Option<bool>,
+    pub daylight: Option<bool>,
+    #[serde(rename = "buttonevent")]
+    pub button_event: Option<u32>,
+    pub status: Option<i32>,
+    /// Relative humidity in hundredths of a percent.
+    pub humidity: Option<u32>,
+    pub open: Option<bool>,
+    #[serde(rename = "lastupdated")]
+    pub last_updated: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct SensorConfig {
+    #[serde(default)]
+    pub on: bool,
+    pub reachable: Option<bool>,
+    pub battery: Option<u8>,
+    pub alert: Option<String>,
+    pub sensitivity: Option<u32>,
+    #[serde(rename = "sensitivitymax")]
+    pub sensitivity_max: Option<u32>,
+    #[serde(rename = "ledindication")]
+    pub led_indication: Option<bool>,
+    #[serde(rename = "usertest")]
+    pub user_test: Option<bool>,
+    #[serde(rename = "tholddark")]
+    pub threshold_dark: Option<u32>,
+    #[serde(rename = "tholdoffset")]
+    pub threshold_offset: Option<u32>,
+    #[serde(rename = "lat")]
+    pub latitude: Option<String>,
+    #[serde(rename = "long")]
+    pub longitude: Option<String>,
+    #[serde(rename = "sunriseoffset")]
+    pub sunrise_offset: Option<i32>,
+    #[serde(rename = "sunsetoffset")]
+    pub sunset_offset: Option<i32>,
+    pub configured: Option<bool>,
+}
+
+/// Bridge that is contacted at the base URL of its address.
+///
+/// The bridge of huelib can only be contacted with HTTP on port 80 of an IP address, so requests
+/// are sent with the same API here to support hostnames, ports, HTTPS and path prefixes.
+#[derive(Clone, Debug)]
+pub struct Bridge {
+    pub address: Address,
+    pub username: String,
+}
+
+#[derive(Clone)]
+enum Method {
+    Get,
+    Put(JsonValue),
+    Post(JsonValue),
+    Delete,
+}
+
+/// Resources whose identifier is not part of their JSON representation.
+trait Identified: DeserializeOwned {
+    fn set_id(&mut self, id: String);
+}
+
+macro_rules! identified {
+    ( $( $type:ty ),* ) => {
+        $(
+            impl Identified for $type {
+                fn set_id(&mut self, id: String) {
+                    self.id = id;
+                }
+            }
+        )*
+    };
+}
+
+identified!(
+    resource::Light,
+    resource::Group,
+    resource::Scene,
+    resource::Schedule,
+    resource::Rule,
+    resource::Resourcelink,
+    Sensor
+);
+
+impl Bridge {
+    pub fn new(address: Address, username: impl Into<String>) -> Self {
+        Self {
+            address,
+            username: username.into(),
+        }
+    }
+
+    pub fn get_config(&self) -> Result<resource::Config> {
+        parse_response(self.request("config", Method::Get)?)
+    }
+
+    pub fn set_config(
+        &self,
+        modifier: &resource::config::Modifier,
+    ) -> Result<Vec<Response<Modified>>> {
+        self.modify("config", modifier)
+    }
+
+    /// Deletes a user from the whitelist of the bridge.
+    pub fn delete_user(&self, id: impl AsRef<str>) -> Result<()> {
+        self.delete(&format!("config/whitelist/{}", id.as_ref()))
+    }
+
+    pub fn get_light(&self, id: impl AsRef<str>) -> Result<resource::Light> {
+        self.get_resource("lights", id.as_ref())
+    }
+
+    pub fn get_all_lights(&self) -> Result<Vec<resource::Light>> {
+        self.get_all_resources("lights")
+    }
+
+    pub fn set_light_attribute(
+        &self,
+        id: impl AsRef<str>,
+        modifier: &resource::light::AttributeModifier,
+    ) -> Result<Vec<Response<Modified>>> {
+        self.modify(&format!("lights/{}", id.as_ref()), modifier)
+    }
+
+    pub fn set_light_state(
+        &self,
+        id: impl AsRef<str>,
+        modifier: &resource::light::StateModifier,
+    ) -> Result<Vec<Response<Modified>>> {
+        self.modify(&format!("lights/{}/state", id.as_ref()), modifier)
+    }
+
+    pub fn search_new_lights(&self, device_ids: Option<&[&str]>) -> Result<()> {
+        self.search("lights", device_ids)
+    }
+
+    pub fn get_new_lights(&self) -> Result<resource::Scan> {
+        parse_response(self.request("lights/new", Method::Get)?)
+    }
+
+    pub fn delete_light(&self, id: impl AsRef<str>) -> Result<()> {
+        self.delete(&format!("lights/{}", id.as_ref()))
+    }
+
+    pub fn create_group(&self, creator: &resource::group::Creator) -> Result<String> {
+        self.create("groups", creator)
+    }
+
+    pub fn get_group(&self, id: impl AsRef<str>) -> Result<resource::Group> {
+        self.get_resource("groups", id.as_ref())
+    }
+
+    pub fn get_all_groups(&self) -> Result<Vec<resource::Group>> {
+        self.get_all_resources("groups")
+    }
+
+    pub fn set_group_attribute(
+        &self,
+        id: impl AsRef<str>,
+        modifier: &resource::group::AttributeModifier,
+    ) -> Result<Vec<Response<Modified>>> {
+        self.modify(&format!("groups/{}", id.as_ref()), modifier)
+    }
+
+    pub fn set_group_state(
+        &self,
+        id: impl AsRef<str>,
+        modifier: &resource::group::StateModifier,
+    ) -> Result<Vec<Response<Modified>>> {
+        self.modify(&format!("groups/{}/action", id.as_ref()), modifier)
+    }
+
+    pub fn delete_group(&self, id: impl AsRef<str>) -> Result<()> {
+        self.delete(&format!("groups/{}", id.as_ref()))
+    }
+
+    pub fn create_scene(&self, creator: &resource::scene::Creator) -> Result<String> {
+        self.create("scenes", creator)
+    }
+
+    pub fn get_scene(&self, id: impl AsRef<str>) -> Result<resource::Scene> {
+        self.get_resource("scenes", id.as_ref())
+    }
+
+    pub fn get_all_scenes(&self) -> Result<Vec<resource::Scene>> {
+        self.get_all_resources("scenes")
+    }
+
+    pub fn set_scene(
+        &self,
+        id: impl AsRef<str>,
+        modifier: &resource::scene::Modifier,
+    ) -> Result<Vec<Response<Modified>>> {
+        self.modify(&format!("scenes/{}", id.as_ref()), modifier)
+    }
+
+    pub fn delete_scene(&self, id: impl AsRef<str>) -> Result<()> {
+        self.delete(&format!("scenes/{}", id.as_ref()))
+    }
+
+    pub fn create_schedule(&self, creator: &resource::schedule::Creator) -> Result<String> {
+        self.create("schedules", creator)
+    }
+
+    pub fn get_schedule(&self, id: impl AsRef<str>) -> Result<resource::Schedule> {
+        self.get_resource("schedules", id.as_ref())
+    }
+
+    pub fn get_all_schedules(&self) -> Result<Vec<resource::Schedule>> {
+        self.get_all_resources("schedules")
+    }
+
+    pub fn set_schedule(
+        &self,
+        id: impl AsRef<str>,
+        modifier: &resource::schedule::Modifier,
+    ) -> Result<Vec<Response<Modified>>> {
+        self.modify(&format!("schedules/{}", id.as_ref()), modifier)
+    }
+
+    pub fn delete_schedule(&self, id: impl AsRef<str>) -> Result<()> {
+        self.delete(&format!("schedules/{}", id.as_ref()))
+    }
+
+    pub fn create_resourcelink(&self, creator: &resource::resourcelink::Creator) -> Result<String> {
+        self.create("resourcelinks", creator)
+    }
+
+    pub fn get_resourcelink(&self, id: impl AsRef<str>) -> Result<resource::Resourcelink> {
+        self.get_resource("resourcelinks", id.as_ref())
+    }
+
+    pub fn get_all_resourcelinks(&self) -> Result<Vec<resource::Resourcelink>> {
+        self.get_all_resources("resourcelinks")
+    }
+
+    pub fn set_resourcelink(
+        &self,
+        id: impl AsRef<str>,
+        modifier: &resource::resourcelink::Modifier,
+    ) -> Result<Vec<Response<Modified>>> {
+        self.modify(&format!("resourcelinks/{}", id.as_ref()), modifier)
+    }
+
+    pub fn delete_resourcelink(&self, id: impl AsRef<str>) -> Result<()> {
+        self.delete(&format!("resourcelinks/{}", id.as_ref()))
+    }
+
+    pub fn get_sensor(&self, id: impl AsRef<str>) -> Result<Sensor> {
+        self.get_resource("sensors", id.as_ref())
+    }
+
+    pub fn get_all_sensors(&self) -> Result<Vec<Sensor>> {
+        self.get_all_resources("sensors")
+    }
+
+    pub fn set_sensor_attribute(
+        &self,
+        id: impl AsRef<str>,
+        modifier: &resource::sensor::AttributeModifier,
+    ) -> Result<Vec<Response<Modified>>> {
+        self.modify(&format!("sensors/{}", id.as_ref()), modifier)
+    }
+
+    pub fn set_sensor_state(
+        &self,
+        id: impl AsRef<str>,
+        modifier: &resource::sensor::StateModifier,
+    ) -> Result<Vec<Response<Modified>>> {
+        self.modify(&format!("sensors/{}/state", id.as_ref()), modifier)
+    }
+
+    pub fn set_sensor_config(
+        &self,
+        id: impl AsRef<str>,
+        modifier: &resource::sensor::ConfigModifier,
+    ) -> Result<Vec<Response<Modified>>> {
+        self.modify(&format!("sensors/{}/config", id.as_ref()), modifier)
+    }
+
+    pub fn search_new_sensors(&self, device_ids: Option<&[&str]>) -> Result<()> {
+        self.search("sensors", device_ids)
+    }
+
+    pub fn get_new_sensors(&self) -> Result<resource::Scan> {
+        parse_response(self.request("sensors/new", Method::Get)?)
+    }
+
+    pub fn delete_sensor(&self, id: impl AsRef<str>) -> Result<()> {
+        self.delete(&format!("sensors/{}", id.as_ref()))
+    }
+
+    pub fn create_rule(&self, creator: &resource::rule::Creator) -> Result<String> {
+        self.create("rules", creator)
+    }
+
+    pub fn get_rule(&self, id: impl AsRef<str>) -> Result<resource::Rule> {
+        self.get_resource("rules", id.as_ref())
+    }
+
+    pub fn get_all_rules(&self) -> Result<Vec<resource::Rule>> {
+        self.get_all_resources("rules")
+    }
+
+    pub fn set_rule(
+        &self,
+        id: impl AsRef<str>,
+        modifier: &resource::rule::Modifier,
+    ) -> Result<Vec<Response<Modified>>> {
+        self.modify(&format!("rules/{}", id.as_ref()), modifier)
+    }
+
+    pub fn delete_rule(&self, id: impl AsRef<str>) -> Result<()> {
+        self.delete(&format!("rules/{}", id.as_ref()))
+    }
+
+    fn get_resource<T: Identified>(&self, kind: &str, id: &str) -> Result<T> {
+        let mut resource: T =
+            parse_response(self.request(&format!("{}/{}", kind, id), Method::Get)?)?;
+        resource.set_id(id.to_owned());
+        Ok(resource)
+    }
+
+    fn get_all_resources<T: Identified>(&self, kind: &str) -> Result<Vec<T>> {
+        let resources: HashMap<String, JsonValue> =
+            parse_response(self.request(kind, Method::Get)?)?;
+        let mut resources: Vec<(String, JsonValue)> = resources.into_iter().collect();
+        resources.sort_by_key(|(id, _)| id.parse::<usize>().unwrap_or(usize::MAX));
+        resources
+            .into_iter()
+            .map(|(id, value)| {
+                let mut resource: T = serde_json::from_value(value)?;
+                resource.set_id(id);
+                Ok(resource)
+            })
+            .collect()
+    }
+
+    fn create(&self, kind: &str, creator: &impl Serialize) -> Result<String> {
+        let body = serde_json::to_value(creator)?;
+        let mut responses: Vec<Response<HashMap<String, String>>> =
+            serde_json::from_value(self.request(kind, Method::Post(body))?)?;
+        match responses.pop() {
+            Some(v) => v.into_result()?.remove("id").ok_or(Error::MissingId),
+            None => Err(Error::MissingId),
+        }
+    }
+
+    fn modify(&self, path: &str, modifier: &impl Serialize) -> Result<Vec<Response<Modified>>> {
+        let body = serde_json::to_value(modifier)?;
+        Ok(serde_json::from_value(
+            self.request(path, Method::Put(body))?,
+        )?)
+    }
+
+    fn delete(&self, path: &str) -> Result<()> {
+        let responses: Vec<Response<JsonValue>> =
+            serde_json::from_value(self.request(path, Method::Delete)?)?;
+        for response in responses {
+            response.into_result()?;
+        }
+        Ok(())
+    }
+
+    fn search(&self, kind: &str, device_ids: Option<&[&str]>) -> Result<()> {
+        let body = match device_ids {
+            Some(v) => serde_json::json!({ "deviceid": v }),
+            None => JsonValue::Null,
+        };
+        let responses: Vec<Response<JsonValue>> =
+            serde_json::from_value(self.request(kind, Method::Post(body))?)?;
+        for response in responses {
+            response.into_result()?;
+        }
+        Ok(())
+    }
+
+    /// Sends a request to the bridge.
+    ///
+    /// If the pinned bridge of the profile is unreachable, it is searched in the local network and
+    /// the request is sent again to its new address.
+    fn request(&self, path: &str, method: Method) -> Result<JsonValue> {
+        let url =
+            |address: &Address| format!("{}/api/{}/{}", address.base_url(), self.username, path);
+        if let Some(v) = util::relocated_address(&self.address) {
+            return send(&url(v), method);
+        }
+        match send(&url(&self.address), method.clone()) {
+            Err(Error::Request(e)) => match util::relocate_bridge(&self.address) {
+                Some(v) => send(&url(v), method),
+                None => Err(Error::Request(e)),
+            },
+            v => v,
+        }
+    }
+}
+
+/// Registers a new user on the bridge at the given address.
+pub fn register_user(
+    address: &Address,
+    device_type: &str,
+    generate_client_key: bool,
+) -> Result<User> {
+    let mut body = serde_json::json!({ "devicetype": device_type });
+    if generate_client_key {
+        body["generateclientkey"] = JsonValue::Bool(true);
+    }
+    let url = format!("{}/api", address.base_url());
+    let mut responses: Vec<Response<User>> =
+        serde_json::from_value(send(&url, Method::Post(body))?)?;
+    match responses.pop() {
+        Some(v) => Ok(v.into_result()?),
+        None => Err(Error::MissingId),
+    }
+}
+
+fn send(url: &str, method: Method) -> Result<JsonValue> {
+    let timeout = REQUEST_TIMEOUT.as_millis() as u64;
+    let mut request = match method {
+        Method::Get => ureq::get(url),
+        Method::Put(_) => ureq::put(url),
+        Method::Post(_) => ureq::post(url),
+        Method::Delete => ureq::delete(url),
+    };
+    request.timeout_connect(timeout).timeout_read(timeout);
+    let response = match method {
+        Method::Put(v) | Method::Post(v) if !v.is_null() => request.send_json(v),
+        _ => request.call(),
+    };
+    if let Some(e) = response.synthetic_error() {
+        return Err(Error::Request(e.to_string()));
+    }
+    let mut body = String::new();
+    if let Err(e) = response.into_reader().read_to_string(&mut body) {
+        return Err(Error::Request(e.to_string()));
+    }
+    Ok(serde_json::from_str(&body)?)
+}
+
+/// Returns the error of the bridge if the response is a list of errors.
+fn parse_response<T: DeserializeOwned>(response: JsonValue) -> Result<T> {
+    if let Ok(mut v) = serde_json::from_value::<Vec<Response<JsonValue>>>(response.clone()) {
+        if let Some(v) = v.pop() {
+            v.into_result()?;
+        }
+    }
+    Ok(serde_json::from_value(response)?)
+}
+
+/// Response of the bridge to a modification, creation or deletion.
+///
+/// The responses of huelib only accept the error types that huelib knows, so errors are
+/// deserialized with their numeric type here.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Response<T> {
+    Success(T),
+    Error(ResponseError),
+}
+
+impl<T> Response<T> {
+    pub fn into_result(self) -> std::result::Result<T, ResponseError> {
+        match self {
+            Self::Success(v) => Ok(v),
+            Self::Error(e) => Err(e),
+        }
+    }
+}
+
+/// Error that is returned by the Hue API.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ResponseError {
+    /// Error type of the Hue API, e.g. `3` if a resource does not exist.
+    #[serde(rename = "type")]
+    pub kind: u16,
+    /// Path of the resource or attribute that caused the error, e.g. `/lights/5/state/hue`.
+    pub address: String,
+    pub description: String,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The request could not be sent or the response could not be received.
+    Request(String),
+    /// The response is not valid JSON or does not have the expected structure.
+    Json(serde_json::Error),
+    /// The bridge returned an error.
+    Response(ResponseError),
+    /// The response does not contain the identifier of the created resource or user.
+    MissingId,
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+impl From<ResponseError> for Error {
+    fn from(e: ResponseError) -> Self {
+        Self::Response(e)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Request(e) => write!(f, "{}", e),
+            Self::Json(e) => write!(f, "Invalid response: {}", e),
+            Self::Response(e) => write!(f, "{}", e.description),
+            Self::MissingId => write!(f, "The response does not contain an identifier"),
+        }
+    }
+}
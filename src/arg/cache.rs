@@ -0,0 +1,27 @@
+use crate::{cache, resolve, util};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+pub enum Arg {
+    /// Requests the cached lights, groups, scenes and sensors from the bridge
+    Refresh,
+    /// Removes the cached resources of the bridge
+    Clear,
+}
+
+pub fn refresh() {
+    let bridge = util::get_bridge();
+    clear();
+    for resource in &cache::RESOURCES {
+        match resolve::candidates(&bridge, *resource) {
+            Ok(v) => println!("Cached {} {}s", v.len(), resource),
+            Err(e) => exit!(format!("Failed to get {}s", resource), e),
+        }
+    }
+}
+
+pub fn clear() {
+    if let Err(e) = cache::clear() {
+        exit!("Failed to clear the cache", e);
+    }
+}
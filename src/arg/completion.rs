@@ -0,0 +1,237 @@
+use crate::{
+    address::Address,
+    arg::{value, Args},
+    resolve::{self, Resource},
+    util,
+};
+use structopt::{clap, StructOpt};
+
+/// Completes the command line with the values of the dynamic completion helper and falls back to
+/// the generated completion function.
+const BASH_SCRIPT: &str = r#"
+_huectl_dynamic() {
+    local candidates
+    candidates=$(huectl complete-words -- "${COMP_WORDS[@]:0:COMP_CWORD+1}" 2>/dev/null)
+    if [[ -n "$candidates" ]]; then
+        local IFS=$'\n'
+        COMPREPLY=($(compgen -W "$candidates" -- "${COMP_WORDS[COMP_CWORD]}"))
+        return 0
+    fi
+    _huectl "$@"
+}
+
+complete -F _huectl_dynamic -o bashdefault -o default huectl
+"#;
+
+const ZSH_SCRIPT: &str = r#"
+_huectl_dynamic() {
+    local -a candidates
+    candidates=("${(@f)$(huectl complete-words -- "${(@)words[1,CURRENT]}" 2>/dev/null)}")
+    if [[ -n "${candidates[1]}" ]]; then
+        compadd -a candidates
+    else
+        _huectl "$@"
+    fi
+}
+
+_huectl_dynamic "$@"
+"#;
+
+const FISH_SCRIPT: &str = r#"
+function __huectl_complete
+    set -l current (commandline -ct)
+    huectl complete-words --descriptions -- (commandline -opc) "$current" 2>/dev/null
+end
+
+complete -c huectl -f -a '(__huectl_complete)'
+"#;
+
+#[derive(Debug, StructOpt)]
+pub struct Completions {
+    /// Shell for which the completion script is generated
+    #[structopt(case_insensitive = true, possible_values = value::Shell::variants())]
+    pub shell: value::Shell,
+}
+
+pub fn completions(arg: Completions) {
+    let shell = match arg.shell {
+        value::Shell::Bash => clap::Shell::Bash,
+        value::Shell::Zsh => clap::Shell::Zsh,
+        value::Shell::Fish => clap::Shell::Fish,
+    };
+    let mut script = Vec::new();
+    Args::clap().gen_completions_to("huectl", shell, &mut script);
+    let script = String::from_utf8_lossy(&script);
+    match arg.shell {
+        value::Shell::Bash => print!("{}{}", script, BASH_SCRIPT),
+        value::Shell::Zsh => {
+            // The generated script ends with a call of its completion function, which is replaced
+            // by a call of the dynamic completion function.
+            let script = script.trim_end().trim_end_matches(r#"_huectl "$@""#);
+            print!("{}{}", script, ZSH_SCRIPT);
+        }
+        value::Shell::Fish => print!("{}{}", script, FISH_SCRIPT),
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub struct Complete {
+    /// Prints a description after each value, separated by a tab
+    #[structopt(long)]
+    pub descriptions: bool,
+    /// Words of the command line including `huectl`, the last word is completed
+    #[structopt(last = true)]
+    pub words: Vec<String>,
+}
+
+impl Complete {
+    /// Returns the values of the `--profile` and `--bridge` options of the completed command
+    /// line.
+    pub fn global_options(&self) -> (Option<String>, Option<Address>) {
+        let value = |name: &str| {
+            self.words
+                .iter()
+                .position(|v| v == name)
+                .and_then(|i| self.words.get(i + 1))
+                .cloned()
+        };
+        (
+            value("--profile"),
+            value("--bridge").and_then(|v| v.parse().ok()),
+        )
+    }
+}
+
+/// Values that complete a word.
+enum Target {
+    Resource(Resource),
+    Values(&'static [&'static str]),
+    Profiles,
+}
+
+/// Global options that take a value, the subcommand and action are the first words that are
+/// neither an option nor the value of one of these options.
+const GLOBAL_VALUE_OPTIONS: &[&str] = &["--profile", "--bridge", "--output", "--error-format"];
+
+fn target(words: &[String]) -> Option<Target> {
+    let (current, previous) = match words {
+        [.., previous, current] => (current.as_str(), previous.as_str()),
+        _ => return None,
+    };
+    if current.starts_with('-') {
+        return None;
+    }
+    let mut positional = Vec::new();
+    for (i, word) in words[..words.len() - 1].iter().enumerate() {
+        let is_value = i > 0 && GLOBAL_VALUE_OPTIONS.contains(&words[i - 1].as_str());
+        if !word.starts_with('-') && !is_value {
+            positional.push(word.as_str());
+        }
+    }
+    let subcommand = positional.get(1).copied().unwrap_or_default();
+    let action = positional.get(2).copied().unwrap_or_default();
+    // Options with multiple values complete every word until the next option.
+    let last_option = words[..words.len() - 1]
+        .iter()
+        .rev()
+        .find(|v| v.starts_with('-'))
+        .map(|v| v.as_str())
+        .unwrap_or_default();
+    let target = match (subcommand, action, previous) {
+        (_, _, "--profile") => Target::Profiles,
+        (_, _, "--output") => Target::Values(value::OutputFormat::variants()),
+        (_, _, "--error-format") => Target::Values(value::ErrorFormat::variants()),
+        ("discover", _, "--method") | ("discover", _, "-m") => {
+            Target::Values(value::DiscoverMethod::variants())
+        }
+        ("register", _, "--shell") => Target::Values(value::Shell::variants()),
+        ("light", "set", "--group") | ("light", "set", "--room") => {
+            Target::Resource(Resource::Group)
+        }
+        ("light", "set", "--alert")
+        | ("light", "set", "-a")
+        | ("group", "set", "--alert")
+        | ("group", "set", "-a") => Target::Values(value::Alert::variants()),
+        ("light", "set", "--effect")
+        | ("light", "set", "-e")
+        | ("group", "set", "--effect")
+        | ("group", "set", "-e") => Target::Values(value::Effect::variants()),
+        ("group", _, "--class") => Target::Values(value::GroupClass::variants()),
+        ("group", "create", "--kind") | ("group", "create", "-k") => {
+            Target::Values(value::GroupTypeCreator::variants())
+        }
+        ("scene", "create", "--kind") | ("scene", "create", "-k") => {
+            Target::Values(value::SceneType::variants())
+        }
+        ("schedule", "create", "--request-type") => {
+            Target::Values(value::ScheduleRequestType::variants())
+        }
+        ("group", _, _) | ("scene", _, _) if ["--lights", "-l"].contains(&last_option) => {
+            Target::Resource(Resource::Light)
+        }
+        ("group", _, _) if last_option == "--sensors" => Target::Resource(Resource::Sensor),
+        ("completions", _, _) if positional.len() == 2 => Target::Values(value::Shell::variants()),
+        (_, "set", _) | (_, "get", _) | (_, "delete", _) if !is_option_value(words) => {
+            match subcommand {
+                "light" => Target::Resource(Resource::Light),
+                "group" => Target::Resource(Resource::Group),
+                "scene" => Target::Resource(Resource::Scene),
+                "sensor" => Target::Resource(Resource::Sensor),
+                "schedule" => Target::Resource(Resource::Schedule),
+                "rule" => Target::Resource(Resource::Rule),
+                "resourcelink" => Target::Resource(Resource::Resourcelink),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    Some(target)
+}
+
+/// Returns whether the completed word is the value of the option before it.
+///
+/// The command line without the completed word is parsed, which fails with a missing value if it
+/// ends with an option that takes one.
+fn is_option_value(words: &[String]) -> bool {
+    match Args::clap().get_matches_from_safe(&words[..words.len() - 1]) {
+        Err(e) => e.kind == clap::ErrorKind::EmptyValue,
+        Ok(_) => false,
+    }
+}
+
+pub fn complete(arg: Complete) {
+    let mut values: Vec<(String, String)> = Vec::new();
+    match target(&arg.words) {
+        Some(Target::Resource(resource)) => {
+            let bridge = util::get_bridge();
+            let candidates = match resolve::candidates(&bridge, resource) {
+                Ok(v) => v,
+                Err(e) => exit!(format!("Failed to get {}s", resource), e),
+            };
+            for candidate in &candidates {
+                values.push((candidate.id.clone(), candidate.name.clone()));
+            }
+            for candidate in candidates {
+                let description = format!("{} {}", resource, candidate.id);
+                values.push((candidate.name, description));
+            }
+        }
+        Some(Target::Values(variants)) => {
+            values.extend(variants.iter().map(|v| ((*v).to_owned(), String::new())));
+        }
+        Some(Target::Profiles) => {
+            if let Ok(file) = crate::config::File::read() {
+                let profiles = file.profiles.keys();
+                values.extend(profiles.map(|v| (v.clone(), "profile".to_owned())));
+            }
+        }
+        None => {}
+    }
+    for (value, description) in values {
+        if arg.descriptions && !description.is_empty() {
+            println!("{}\t{}", value, description);
+        } else {
+            println!("{}", value);
+        }
+    }
+}
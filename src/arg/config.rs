@@ -1,4 +1,4 @@
-use crate::{output::Config as OutputConfig, util};
+use crate::{output, output::Config as OutputConfig, util};
 use huelib::resource::{config, Modifier};
 use std::net::IpAddr;
 use structopt::StructOpt;
@@ -101,22 +101,20 @@ impl Set {
 }
 
 pub fn set(arg: Set) {
-    let responses = match util::get_bridge().set_config(&arg.to_modifier()) {
+    let modifier = arg.to_modifier();
+    let responses = match util::get_bridge().set_config(&modifier) {
         Ok(v) => v,
         Err(e) => exit!("Failed to set config", e),
     };
-    for i in responses {
-        println!("{}", i);
-    }
+    let mut modifications = output::Modifications::default();
+    modifications.extend(responses, &modifier);
+    util::print_modifications(&modifications);
 }
 
 pub fn get() {
     let bridge = util::get_bridge();
     match bridge.get_config() {
-        Ok(v) => println!(
-            "{}",
-            serde_json::to_string_pretty(&OutputConfig::from(v)).unwrap()
-        ),
+        Ok(v) => output::print(&OutputConfig::from(v)),
         Err(e) => exit!("Failed to get config", e),
     };
 }
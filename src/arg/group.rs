@@ -1,5 +1,13 @@
-use crate::{arg::value, output::Group as OutputGroup, util};
-use huelib::resource::{group, Modifier};
+use crate::{
+    api,
+    arg::{self, value},
+    error::{Error, Kind},
+    output,
+    output::Group as OutputGroup,
+    resolve::Resource,
+    select, util,
+};
+use huelib::resource::{group, light, Modifier, ModifierType};
 use huelib::Color;
 use structopt::StructOpt;
 
@@ -17,8 +25,17 @@ pub enum Arg {
 
 #[derive(Debug, StructOpt)]
 pub struct Set {
-    /// Identifier of the group
-    pub id: String,
+    /// Identifiers, names or selector expressions of the groups, e.g. `zone:Upstairs`
+    pub ids: Vec<String>,
+    /// Prints the groups that are matched by each part of a selector expression
+    #[structopt(long)]
+    explain: bool,
+    /// Selects all groups
+    #[structopt(long)]
+    all: bool,
+    /// Selects only groups that match the filter, e.g. `class=Kitchen` or `kind=Room`
+    #[structopt(long, number_of_values = 1)]
+    filter: Vec<value::Filter>,
     /// Turns the lights on
     #[structopt(long)]
     on: bool,
@@ -34,9 +51,12 @@ pub struct Set {
     /// Sets the saturation of the lights in percentage
     #[structopt(long, short, allow_hyphen_values = true)]
     saturation: Option<value::Saturation>,
-    /// Sets the color temperature of the lights
+    /// Sets the color temperature of the lights in Kelvin or mireds, e.g. `2700K`, `370` or `-50`
     #[structopt(long, short = "t", allow_hyphen_values = true)]
     color_temperature: Option<value::ColorTemperature>,
+    /// Clamps the color temperature to the range that the lights support
+    #[structopt(long)]
+    clamp: bool,
     /// Sets the x and y coordinates in the color space of the lights
     #[structopt(long, short, name = "coordinate", min_values = 2, max_values = 2)]
     color_space_coordinates: Option<Vec<f32>>,
@@ -70,7 +90,12 @@ pub struct Set {
 }
 
 impl Set {
-    pub fn to_state_modifier(&self) -> group::StateModifier {
+    /// Returns the modifier of the state, the color temperature is given in mireds because it
+    /// depends on the lights of the group.
+    pub fn to_state_modifier(
+        &self,
+        color_temperature: Option<(ModifierType, u16)>,
+    ) -> group::StateModifier {
         let mut modifier = group::StateModifier::new();
         if self.on {
             modifier = modifier.on(true);
@@ -95,7 +120,7 @@ impl Set {
         if let Some(v) = &self.color_hex {
             modifier = modifier.color(v.0);
         }
-        if let Some(v) = &self.color_temperature {
+        if let Some(v) = color_temperature {
             modifier = modifier.color_temperature(v.0, v.1);
         }
         if let Some(v) = &self.alert {
@@ -126,52 +151,159 @@ impl Set {
         }
         modifier
     }
+
+    /// Returns the identifiers of the selected groups.
+    ///
+    /// The groups given by identifier, selector expression or `--all` are narrowed down by
+    /// filters. If only filters are given, they are applied to all groups.
+    pub fn select(&self, bridge: &api::Bridge) -> Vec<String> {
+        let ids = util::select_ids(bridge, Resource::Group, &self.ids, self.explain);
+        if !self.all && self.filter.is_empty() {
+            return util::unique(ids);
+        }
+        let groups = match bridge.get_all_groups() {
+            Ok(v) => v,
+            Err(e) => exit!("Failed to get groups", e),
+        };
+        let mut matched = Vec::new();
+        for group in groups {
+            if !self.all && !self.ids.is_empty() && !ids.contains(&group.id) {
+                continue;
+            }
+            let id = group.id.clone();
+            let value = serde_json::to_value(OutputGroup::from(group)).unwrap_or_default();
+            if self.filter.iter().all(|v| v.0.matches(&value)) {
+                matched.push(id);
+            }
+        }
+        matched
+    }
 }
 
-pub fn set(arg: Set) {
+pub fn set(mut arg: Set) {
     let bridge = util::get_bridge();
-    let mut responses = Vec::new();
-    let state_modifier = arg.to_state_modifier();
-    if !state_modifier.is_empty() {
-        responses.extend(match bridge.set_group_state(&arg.id, &state_modifier) {
-            Ok(v) => v,
-            Err(e) => exit!("Error occured while modifying the state of the lights", e),
-        });
+    let ids = arg.select(&bridge);
+    if ids.is_empty() {
+        Error::new(Kind::NotFound, "No groups match the selection").exit();
+    }
+    if ids.len() > 1 && arg.name.is_some() {
+        Error::new(Kind::InvalidParameter, "Multiple groups can not be renamed").exit();
     }
+    arg.lights = arg
+        .lights
+        .map(|v| util::select_ids(&bridge, Resource::Light, &v, false));
+    arg.sensors = arg
+        .sensors
+        .map(|v| util::resolve_ids(&bridge, Resource::Sensor, &v));
+    if let Some(value::ColorTemperature(modifier_type, _, value::ColorTemperatureUnit::Kelvin)) =
+        &arg.color_temperature
+    {
+        // A relative change in Kelvin is a different change in mireds for every light, but the
+        // bridge sets the same value on all lights of a group.
+        if *modifier_type != ModifierType::Override {
+            Error::new(
+                Kind::InvalidParameter,
+                "The color temperature of groups can only be changed relatively in mireds",
+            )
+            .exit();
+        }
+    }
+    let (groups, lights) = match &arg.color_temperature {
+        Some(_) => match (bridge.get_all_groups(), bridge.get_all_lights()) {
+            (Ok(groups), Ok(lights)) => (groups, lights),
+            (Err(e), _) => exit!("Failed to get groups", e),
+            (_, Err(e)) => exit!("Failed to get lights", e),
+        },
+        None => (Vec::new(), Vec::new()),
+    };
+    let mut modifications = output::Modifications::default();
     let attribute_modifier = arg.to_attribute_modifier();
-    if !attribute_modifier.is_empty() {
-        responses.extend(
-            match bridge.set_group_attribute(&arg.id, &attribute_modifier) {
+    for id in &ids {
+        let color_temperature = arg.color_temperature.as_ref().map(|value| {
+            let group_lights: Vec<String> = groups
+                .iter()
+                .find(|v| &v.id == id)
+                .map(|v| v.lights.clone())
+                .unwrap_or_default();
+            let lights: Vec<light::Light> = lights
+                .iter()
+                .filter(|v| group_lights.contains(&v.id))
+                .cloned()
+                .collect();
+            if !arg.clamp {
+                if let Some(v) = value.exceeded_range(&lights) {
+                    eprintln!("{}", v);
+                }
+            }
+            match value.to_mired(&lights, arg.clamp) {
                 Ok(v) => v,
-                Err(e) => exit!("Error occured while modifying attributes of the lights", e),
-            },
-        );
-    }
-    for i in responses {
-        println!("{}", i);
+                Err(e) => Error::new(
+                    Kind::InvalidParameter,
+                    format!("Invalid color temperature for group {}: {}", id, e),
+                )
+                .exit(),
+            }
+        });
+        let state_modifier = arg.to_state_modifier(color_temperature);
+        if !state_modifier.is_empty() {
+            modifications.extend(
+                match bridge.set_group_state(id, &state_modifier) {
+                    Ok(v) => v,
+                    Err(e) => exit!("Error occured while modifying the state of the lights", e),
+                },
+                &state_modifier,
+            );
+        }
+        if !attribute_modifier.is_empty() {
+            modifications.extend(
+                match bridge.set_group_attribute(id, &attribute_modifier) {
+                    Ok(v) => v,
+                    Err(e) => exit!("Error occured while modifying attributes of the lights", e),
+                },
+                &attribute_modifier,
+            );
+        }
     }
+    util::print_modifications(&modifications);
 }
 
 #[derive(Debug, StructOpt)]
 pub struct Get {
-    /// Identifier of the group, if omitted all groups are selected
+    /// Identifier, name or selector expression of the groups, if omitted all groups are selected
     pub id: Option<String>,
+    /// Prints the groups that are matched by each part of a selector expression
+    #[structopt(long)]
+    pub explain: bool,
+    #[structopt(flatten)]
+    pub options: arg::GetOptions,
 }
 
 pub fn get(arg: Get) {
     let bridge = util::get_bridge();
+    let options = arg.options.to_output_options();
     match arg.id {
-        Some(v) => match bridge.get_group(&v) {
-            Ok(v) => println!(
-                "{}",
-                serde_json::to_string_pretty(&OutputGroup::from(v)).unwrap()
-            ),
+        Some(v) if select::is_expression(&v) => {
+            let ids = util::select_groups(&bridge, &v, arg.explain);
+            match bridge.get_all_groups() {
+                Ok(v) => {
+                    let groups: Vec<OutputGroup> = v
+                        .into_iter()
+                        .filter(|v| ids.contains(&v.id))
+                        .map(OutputGroup::from)
+                        .collect();
+                    output::print_with(&groups, &options);
+                }
+                Err(e) => exit!("Failed to get groups", e),
+            }
+        }
+        Some(v) => match bridge.get_group(util::resolve_id(&bridge, Resource::Group, &v)) {
+            Ok(v) => output::print_with(&OutputGroup::from(v), &options),
             Err(e) => exit!("Failed to get group", e),
         },
         None => match bridge.get_all_groups() {
             Ok(v) => {
                 let groups: Vec<OutputGroup> = v.into_iter().map(OutputGroup::from).collect();
-                println!("{}", serde_json::to_string_pretty(&groups).unwrap());
+                output::print_with(&groups, &options);
             }
             Err(e) => exit!("Failed to get groups", e),
         },
@@ -182,10 +314,10 @@ pub fn get(arg: Get) {
 pub struct Create {
     /// The name of the new group
     name: String,
-    /// Sets the indentifiers of the lights that will be in this group
+    /// Sets the identifiers or names of the lights that will be in this group
     #[structopt(long, short)]
     lights: Vec<String>,
-    /// Sets the indentifiers of the senors that will be in this group
+    /// Sets the identifiers or names of the sensors that will be in this group
     #[structopt(long)]
     sensors: Option<Vec<String>>,
     /// Sets the type of the group
@@ -223,8 +355,13 @@ impl Create {
     }
 }
 
-pub fn create(arg: Create) {
-    match util::get_bridge().create_group(&arg.to_creator()) {
+pub fn create(mut arg: Create) {
+    let bridge = util::get_bridge();
+    arg.lights = util::select_ids(&bridge, Resource::Light, &arg.lights, false);
+    arg.sensors = arg
+        .sensors
+        .map(|v| util::resolve_ids(&bridge, Resource::Sensor, &v));
+    match bridge.create_group(&arg.to_creator()) {
         Ok(v) => println!("Created group {}", v),
         Err(e) => exit!("Failed to create group", e),
     }
@@ -232,13 +369,15 @@ pub fn create(arg: Create) {
 
 #[derive(Debug, StructOpt)]
 pub struct Delete {
-    /// Identifier of the group
+    /// Identifier or exact name of the group, the case of the name is ignored
     pub id: String,
 }
 
 pub fn delete(arg: Delete) {
-    match util::get_bridge().delete_group(&arg.id) {
-        Ok(_) => println!("Deleted group {}", arg.id),
+    let bridge = util::get_bridge();
+    let id = util::resolve_exact_id(&bridge, Resource::Group, &arg.id);
+    match bridge.delete_group(&id) {
+        Ok(_) => println!("Deleted group {}", id),
         Err(e) => exit!("Failed to delete group", e),
     };
 }
@@ -1,5 +1,14 @@
-use crate::{arg::value, output::Light as OutputLight, output::Scan as OutputScan, util};
-use huelib::resource::{light, Modifier};
+use crate::{
+    api,
+    arg::{self, value},
+    error::{Error, Kind},
+    output,
+    output::Light as OutputLight,
+    output::Scan as OutputScan,
+    resolve::{self, Resource},
+    select, util,
+};
+use huelib::resource::{light, Modifier, ModifierType};
 use huelib::Color;
 use structopt::StructOpt;
 
@@ -17,8 +26,26 @@ pub enum Arg {
 
 #[derive(Debug, StructOpt)]
 pub struct Set {
-    /// Identifier of the light
-    pub id: String,
+    /// Identifiers, names or selector expressions of the lights, e.g. `room:Kitchen & on:true`
+    pub ids: Vec<String>,
+    /// Prints the lights that are matched by each part of a selector expression
+    #[structopt(long)]
+    explain: bool,
+    /// Selects all lights
+    #[structopt(long)]
+    all: bool,
+    /// Selects the lights of the group with the given identifier or name
+    #[structopt(long, number_of_values = 1)]
+    group: Vec<String>,
+    /// Selects the lights of the room with the given name
+    #[structopt(long, number_of_values = 1)]
+    room: Vec<String>,
+    /// Selects only lights whose type contains the value, e.g. `color` or `dimmable`
+    #[structopt(long = "type")]
+    kind: Option<String>,
+    /// Selects only lights that match the filter, e.g. `name~desk` or `reachable=true`
+    #[structopt(long, number_of_values = 1)]
+    filter: Vec<value::Filter>,
     /// Turns the light on
     #[structopt(long)]
     on: bool,
@@ -34,9 +61,12 @@ pub struct Set {
     /// Sets the saturation of the light in percentage
     #[structopt(long, short, allow_hyphen_values = true)]
     saturation: Option<value::Saturation>,
-    /// Sets the color temperature of the light
+    /// Sets the color temperature of the light in Kelvin or mireds, e.g. `2700K`, `+500K` or `370`
     #[structopt(long, short = "t", allow_hyphen_values = true)]
     color_temperature: Option<value::ColorTemperature>,
+    /// Clamps the color temperature to the range that the light supports
+    #[structopt(long)]
+    clamp: bool,
     /// Sets the x and y coordinates in the color space of the light
     #[structopt(long, short, name = "coordinate", min_values = 2, max_values = 2)]
     color_space_coordinates: Option<Vec<f32>>,
@@ -61,7 +91,12 @@ pub struct Set {
 }
 
 impl Set {
-    pub fn to_state_modifier(&self) -> light::StateModifier {
+    /// Returns the modifier of the state, the color temperature is given in mireds because it
+    /// depends on the light.
+    pub fn to_state_modifier(
+        &self,
+        color_temperature: Option<(ModifierType, u16)>,
+    ) -> light::StateModifier {
         let mut modifier = light::StateModifier::new();
         if self.on {
             modifier = modifier.on(true);
@@ -86,7 +121,7 @@ impl Set {
         if let Some(v) = &self.color_hex {
             modifier = modifier.color(v.0);
         }
-        if let Some(v) = &self.color_temperature {
+        if let Some(v) = color_temperature {
             modifier = modifier.color_temperature(v.0, v.1);
         }
         if let Some(v) = &self.alert {
@@ -108,52 +143,189 @@ impl Set {
         }
         modifier
     }
+
+    /// Returns the identifiers of the selected lights.
+    ///
+    /// The lights given by identifier, selector expression, group, room or `--all` are narrowed
+    /// down by type and filters. If only a type or filters are given, they are applied to all
+    /// lights.
+    pub fn select(&self, bridge: &api::Bridge) -> Vec<String> {
+        let mut ids = util::select_ids(bridge, Resource::Light, &self.ids, self.explain);
+        for group_id in util::select_ids(bridge, Resource::Group, &self.group, self.explain) {
+            match bridge.get_group(&group_id) {
+                Ok(v) => ids.extend(v.lights),
+                Err(e) => exit!("Failed to get group", e),
+            }
+        }
+        if !self.room.is_empty() {
+            ids.extend(room_lights(bridge, &self.room));
+        }
+        let narrowed = self.kind.is_some() || !self.filter.is_empty();
+        let selected = !self.ids.is_empty() || !self.group.is_empty() || !self.room.is_empty();
+        if !self.all && !narrowed {
+            return util::unique(ids);
+        }
+        let lights = match bridge.get_all_lights() {
+            Ok(v) => v,
+            Err(e) => exit!("Failed to get lights", e),
+        };
+        let kind = self.kind.as_ref().map(|v| v.to_lowercase());
+        let lights = lights.into_iter().filter(|v| {
+            let matches_kind = match &kind {
+                Some(kind) => v.kind.to_lowercase().contains(kind),
+                None => true,
+            };
+            (self.all || !selected || ids.contains(&v.id)) && matches_kind
+        });
+        let mut matched = Vec::new();
+        for light in lights {
+            let id = light.id.clone();
+            let value = serde_json::to_value(OutputLight::from(light)).unwrap_or_default();
+            if self.filter.iter().all(|v| v.0.matches(&value)) {
+                matched.push(id);
+            }
+        }
+        matched
+    }
+}
+
+/// Returns the identifiers of the lights in the rooms with the given names.
+fn room_lights(bridge: &api::Bridge, names: &[String]) -> Vec<String> {
+    use huelib::resource::group::{self, CreatableKind};
+    let rooms: Vec<huelib::resource::Group> = match bridge.get_all_groups() {
+        Ok(v) => v
+            .into_iter()
+            .filter(|v| matches!(v.kind, group::Kind::Creatable(CreatableKind::Room)))
+            .collect(),
+        Err(e) => exit!("Failed to get groups", e),
+    };
+    let candidates: Vec<resolve::Candidate> = rooms
+        .iter()
+        .map(|v| resolve::Candidate {
+            id: v.id.clone(),
+            name: v.name.clone(),
+        })
+        .collect();
+    let mut ids = Vec::new();
+    for name in names {
+        let id = match resolve::find(&candidates, Resource::Group, name) {
+            Ok(v) => v,
+            Err(e) => exit!("Failed to find room", e),
+        };
+        if let Some(room) = rooms.iter().find(|v| v.id == id) {
+            ids.extend(room.lights.iter().cloned());
+        }
+    }
+    ids
 }
 
 pub fn set(arg: Set) {
     let bridge = util::get_bridge();
-    let mut responses = Vec::new();
-    let state_modifier = arg.to_state_modifier();
-    if !state_modifier.is_empty() {
-        responses.extend(match bridge.set_light_state(&arg.id, &state_modifier) {
-            Ok(v) => v,
-            Err(e) => exit!("Error occured while modifying the state of the light", e),
-        });
+    let ids = arg.select(&bridge);
+    if ids.is_empty() {
+        Error::new(Kind::NotFound, "No lights match the selection").exit();
     }
     let attribute_modifier = arg.to_attribute_modifier();
-    if !attribute_modifier.is_empty() {
-        responses.extend(
-            match bridge.set_light_attribute(&arg.id, &attribute_modifier) {
-                Ok(v) => v,
-                Err(e) => exit!("Error occured while modifying attributes of the light", e),
-            },
-        );
+    if ids.len() > 1 && !attribute_modifier.is_empty() {
+        Error::new(Kind::InvalidParameter, "Multiple lights can not be renamed").exit();
     }
-    for i in responses {
-        println!("{}", i);
+    let lights = match &arg.color_temperature {
+        Some(_) => match bridge.get_all_lights() {
+            Ok(v) => v,
+            Err(e) => exit!("Failed to get lights", e),
+        },
+        None => Vec::new(),
+    };
+    let mut modifications = output::Modifications::default();
+    for id in &ids {
+        let color_temperature = arg.color_temperature.as_ref().map(|value| {
+            let selected: Vec<light::Light> =
+                lights.iter().filter(|v| &v.id == id).cloned().collect();
+            if !arg.clamp {
+                if let Some(v) = value.exceeded_range(&selected) {
+                    eprintln!("{}", v);
+                }
+            }
+            match value.to_mired(&selected, arg.clamp) {
+                Ok(v) => v,
+                Err(e) => Error::new(
+                    Kind::InvalidParameter,
+                    format!("Invalid color temperature for light {}: {}", id, e),
+                )
+                .exit(),
+            }
+        });
+        let state_modifier = arg.to_state_modifier(color_temperature);
+        if !state_modifier.is_empty() {
+            modifications.extend(
+                match bridge.set_light_state(id, &state_modifier) {
+                    Ok(v) => v,
+                    Err(e) => exit!("Error occured while modifying the state of the light", e),
+                },
+                &state_modifier,
+            );
+        }
+        if !attribute_modifier.is_empty() {
+            modifications.extend(
+                match bridge.set_light_attribute(id, &attribute_modifier) {
+                    Ok(v) => v,
+                    Err(e) => exit!("Error occured while modifying attributes of the light", e),
+                },
+                &attribute_modifier,
+            );
+        }
     }
+    util::print_modifications(&modifications);
 }
 
 #[derive(Debug, StructOpt)]
 pub struct Get {
-    /// Identifier of the light, if omitted all lights are selected
+    /// Identifier, name or selector expression of the lights, if omitted all lights are selected
     pub id: Option<String>,
+    /// Prints the lights that are matched by each part of a selector expression
+    #[structopt(long)]
+    pub explain: bool,
+    /// Prints only the values sent by the bridge, without values in common units
+    #[structopt(long)]
+    pub raw: bool,
+    #[structopt(flatten)]
+    pub options: arg::GetOptions,
 }
 
 pub fn get(arg: Get) {
     let bridge = util::get_bridge();
+    let options = arg.options.to_output_options();
+    let raw = arg.raw;
+    let to_output = move |v: light::Light| {
+        if raw {
+            OutputLight::from(v).into_raw()
+        } else {
+            OutputLight::from(v)
+        }
+    };
     match arg.id {
-        Some(v) => match bridge.get_light(&v) {
-            Ok(v) => println!(
-                "{}",
-                serde_json::to_string_pretty(&OutputLight::from(v)).unwrap()
-            ),
+        Some(v) if select::is_expression(&v) => {
+            let ids = util::select_lights(&bridge, &v, arg.explain);
+            match bridge.get_all_lights() {
+                Ok(v) => {
+                    let lights: Vec<OutputLight> = v
+                        .into_iter()
+                        .filter(|v| ids.contains(&v.id))
+                        .map(to_output)
+                        .collect();
+                    output::print_with(&lights, &options);
+                }
+                Err(e) => exit!("Failed to get lights", e),
+            }
+        }
+        Some(v) => match bridge.get_light(util::resolve_id(&bridge, Resource::Light, &v)) {
+            Ok(v) => output::print_with(&to_output(v), &options),
             Err(e) => exit!("Failed to get light", e),
         },
         None => match bridge.get_all_lights() {
             Ok(v) => {
-                let lights: Vec<OutputLight> = v.into_iter().map(OutputLight::from).collect();
-                println!("{}", serde_json::to_string_pretty(&lights).unwrap());
+                let lights: Vec<OutputLight> = v.into_iter().map(to_output).collect();
+                output::print_with(&lights, &options);
             }
             Err(e) => exit!("Failed to get lights", e),
         },
@@ -171,10 +343,7 @@ pub fn search(arg: Search) {
     let bridge = util::get_bridge();
     if arg.get {
         match bridge.get_new_lights() {
-            Ok(v) => println!(
-                "{}",
-                serde_json::to_string_pretty(&OutputScan::from(v)).unwrap()
-            ),
+            Ok(v) => output::print(&OutputScan::from(v)),
             Err(e) => exit!("Failed to get new lights", e),
         };
     } else {
@@ -187,13 +356,15 @@ pub fn search(arg: Search) {
 
 #[derive(Debug, StructOpt)]
 pub struct Delete {
-    /// Identifier of the light
+    /// Identifier or exact name of the light, the case of the name is ignored
     pub id: String,
 }
 
 pub fn delete(arg: Delete) {
-    match util::get_bridge().delete_light(&arg.id) {
-        Ok(_) => println!("Deleted light {}", arg.id),
+    let bridge = util::get_bridge();
+    let id = util::resolve_exact_id(&bridge, Resource::Light, &arg.id);
+    match bridge.delete_light(&id) {
+        Ok(_) => println!("Deleted light {}", id),
         Err(e) => exit!("Failed to delete light", e),
     };
 }
@@ -1,3 +1,5 @@
+mod cache;
+mod completion;
 mod config;
 mod group;
 mod light;
@@ -6,15 +8,43 @@ mod rule;
 mod scene;
 mod schedule;
 mod sensor;
+mod user;
 mod value;
 
-use std::{fmt, net::IpAddr};
+use crate::{
+    address::Address,
+    error::{self, Categorize, Error, Kind},
+    output,
+    output::Bridge as OutputBridge,
+};
+use std::{
+    fmt,
+    io::{self, Write},
+    thread,
+    time::{Duration, Instant},
+};
 use structopt::StructOpt;
 
 pub fn exec() {
-    let args = Args::from_args();
+    let mut args = Args::from_args();
+    if let Subcommand::Complete(v) = &args.subcommand {
+        // The bridge of the completed command line is used to complete identifiers and names.
+        let (profile, bridge) = v.global_options();
+        args.profile = profile.or(args.profile.take());
+        args.bridge = bridge.or(args.bridge.take());
+    }
+    crate::config::init(crate::config::Options {
+        profile: args.profile,
+        bridge_address: args.bridge,
+    });
+    output::init(args.output.0);
+    error::init(args.error_format.0);
+    crate::cache::init(crate::cache::Options {
+        enabled: !args.no_cache && !args.subcommand.deletes_resources(),
+        modifies: args.subcommand.modifies_resources(),
+    });
     match args.subcommand {
-        Subcommand::Discover => discover(),
+        Subcommand::Discover(v) => discover(v),
         Subcommand::Register(v) => register(v),
         Subcommand::Config(v) => match v {
             config::Arg::Set(v) => config::set(v),
@@ -62,12 +92,38 @@ pub fn exec() {
             sensor::Arg::Search(v) => sensor::search(v),
             sensor::Arg::Delete(v) => sensor::delete(v),
         },
+        Subcommand::User(v) => match v {
+            user::Arg::List => user::list(),
+            user::Arg::Delete(v) => user::delete(v),
+            user::Arg::Prune(v) => user::prune(v),
+        },
+        Subcommand::Cache(v) => match v {
+            cache::Arg::Refresh => cache::refresh(),
+            cache::Arg::Clear => cache::clear(),
+        },
+        Subcommand::Completions(v) => completion::completions(v),
+        Subcommand::Complete(v) => completion::complete(v),
     };
 }
 
 /// A command line interface to Philips Hue
 #[derive(Debug, StructOpt)]
 pub struct Args {
+    /// Name of the profile in the configuration file that is used
+    #[structopt(long, global = true, env = crate::config::VAR_PROFILE)]
+    pub profile: Option<String>,
+    /// Address of the bridge (IP address, hostname or URL), overrides the address of the profile
+    #[structopt(long, global = true)]
+    pub bridge: Option<Address>,
+    /// Sets the format of the output
+    #[structopt(long, global = true, case_insensitive = true, default_value = "json", possible_values = value::OutputFormat::variants())]
+    pub output: value::OutputFormat,
+    /// Sets the format of errors that are printed to stderr
+    #[structopt(long, global = true, case_insensitive = true, default_value = "text", possible_values = value::ErrorFormat::variants())]
+    pub error_format: value::ErrorFormat,
+    /// Requests lights, groups, scenes and sensors from the bridge instead of using the cache
+    #[structopt(long, global = true)]
+    pub no_cache: bool,
     #[structopt(subcommand)]
     pub subcommand: Subcommand,
 }
@@ -75,7 +131,7 @@ pub struct Args {
 #[derive(Debug, StructOpt)]
 pub enum Subcommand {
     /// Discovers bridges in the local network
-    Discover,
+    Discover(Discover),
     /// Registers a new user on a bridge
     Register(Register),
     /// Modifies or prints the bridge configuration
@@ -94,55 +150,208 @@ pub enum Subcommand {
     Schedule(schedule::Arg),
     /// Modifies, prints, searches or deletes sensors
     Sensor(sensor::Arg),
+    /// Prints or deletes users that are registered on the bridge
+    User(user::Arg),
+    /// Refreshes or clears the cached lights, groups, scenes and sensors
+    Cache(cache::Arg),
+    /// Prints a completion script for the given shell
+    Completions(completion::Completions),
+    /// Prints the values that complete a command line, used by the completion scripts
+    #[structopt(name = "complete-words", setting = structopt::clap::AppSettings::Hidden)]
+    Complete(completion::Complete),
+}
+
+impl Subcommand {
+    /// Returns whether the subcommand can modify cached resources.
+    fn modifies_resources(&self) -> bool {
+        match self {
+            Self::Light(v) => !matches!(v, light::Arg::Get(_)),
+            Self::Group(v) => !matches!(v, group::Arg::Get(_)),
+            Self::Scene(v) => !matches!(v, scene::Arg::Get(_)),
+            Self::Sensor(v) => !matches!(v, sensor::Arg::Get(_)),
+            _ => false,
+        }
+    }
+
+    /// Returns whether the subcommand deletes resources that are given by name.
+    fn deletes_resources(&self) -> bool {
+        matches!(
+            self,
+            Self::Light(light::Arg::Delete(_))
+                | Self::Group(group::Arg::Delete(_))
+                | Self::Scene(scene::Arg::Delete(_))
+                | Self::Sensor(sensor::Arg::Delete(_))
+        )
+    }
 }
 
-pub fn discover() {
-    let ip_addresses = match huelib::bridge::discover() {
+#[derive(Debug, StructOpt)]
+pub struct Discover {
+    /// Sets the method that is used to discover bridges
+    #[structopt(long, short, case_insensitive = true, default_value = "nupnp", possible_values = value::DiscoverMethod::variants())]
+    pub method: value::DiscoverMethod,
+    /// Sets how long to wait for responses of the bridges
+    #[structopt(long, short, default_value = "3s")]
+    pub timeout: value::Duration,
+}
+
+pub fn discover(arg: Discover) {
+    let ip_addresses = match crate::discover::discover(arg.method.0, arg.timeout.0) {
         Ok(v) => v,
         Err(e) => exit!("Failed to discover bridges", e),
     };
-    for i in ip_addresses {
-        println!("{}", i);
-    }
+    let bridges: Vec<OutputBridge> = ip_addresses
+        .into_iter()
+        .map(|v| {
+            let config = crate::discover::get_config(&Address::from(v), arg.timeout.0);
+            OutputBridge::new(v, config.ok())
+        })
+        .collect();
+    output::print(&bridges);
 }
 
 #[derive(Debug, StructOpt)]
 pub struct Register {
-    /// IP address of the bridge, if omitted the user will be registered on the first discovered
+    /// Address of the bridge, if omitted the user will be registered on the first discovered
     /// bridge
-    pub ip_address: Option<IpAddr>,
-    /// Sets environment variables for the current session
+    pub address: Option<Address>,
+    /// Saves the credentials in the given profile of the configuration file
     #[structopt(long, short)]
-    pub set_env: bool,
+    pub save: Option<String>,
+    /// Prints the credentials as export commands for the given shell
+    #[structopt(long, case_insensitive = true, possible_values = value::Shell::variants())]
+    pub shell: Option<value::Shell>,
+    /// Retries the registration until the link button is pressed or the duration (e.g. `30s` or
+    /// `2m`) has elapsed
+    #[structopt(long, short)]
+    pub wait: Option<value::Duration>,
+    /// Sets the device type of the user
+    #[structopt(long, short, default_value = "huectl-rs")]
+    pub device_type: String,
+    /// Generates a client key for the entertainment streaming
+    #[structopt(long, short)]
+    pub client_key: bool,
 }
 
 pub fn register(arg: Register) {
-    let ip_address = match arg.ip_address {
+    let address = match arg.address {
         Some(v) => v,
         None => match huelib::bridge::discover() {
             Ok(mut v) => match v.pop() {
-                Some(v) => v,
-                None => exit!("No bridges were found"),
+                Some(v) => Address::from(v),
+                None => Error::new(Kind::BridgeUnreachable, "No bridges were found").exit(),
             },
             Err(e) => exit!("Failed to discover bridges", e),
         },
     };
-    let user = match huelib::bridge::register_user(ip_address, "huectl-rs", false) {
-        Ok(v) => v,
-        Err(e) => exit!(
-            format!(
-                "Failed to register user on bridge with the IP address '{}'",
-                ip_address
-            ),
-            e
-        ),
+    let register_error = format!(
+        "Failed to register user on bridge with the address '{}'",
+        address
+    );
+    let deadline = Instant::now() + arg.wait.map(|v| v.0).unwrap_or_default();
+    let user = loop {
+        match crate::api::register_user(&address, &arg.device_type, arg.client_key) {
+            Ok(v) => break v,
+            Err(e) if e.kind() == Kind::LinkButtonNotPressed => {
+                let now = Instant::now();
+                if now >= deadline {
+                    if arg.wait.is_some() {
+                        eprintln!();
+                    }
+                    exit!(register_error, e);
+                }
+                eprint!(
+                    "\rPress the link button of the bridge ({}s remaining) ",
+                    (deadline - now).as_secs()
+                );
+                let _ = io::stderr().flush();
+                thread::sleep(Duration::from_secs(1));
+            }
+            Err(e) => exit!(register_error, e),
+        }
     };
-    if arg.set_env {
-        std::env::set_var(crate::config::VAR_BRIDGE_IP, ip_address.to_string());
-        std::env::set_var(crate::config::VAR_BRIDGE_USERNAME, user.name);
-    } else {
-        println!("{}={}", crate::config::VAR_BRIDGE_IP, ip_address);
-        println!("{}={}", crate::config::VAR_BRIDGE_USERNAME, user.name);
+    if arg.wait.is_some() {
+        eprintln!("\rLink button was pressed{:30}", "");
+    }
+    if let Some(profile_name) = arg.save {
+        let bridge_id = match crate::api::Bridge::new(address.clone(), &user.name).get_config() {
+            Ok(v) => Some(v.bridge_id),
+            Err(_) => None,
+        };
+        let mut file = match crate::config::File::read() {
+            Ok(v) => v,
+            Err(e) => exit!("Failed to read configuration file", e),
+        };
+        file.profiles.insert(
+            profile_name.clone(),
+            crate::config::Profile {
+                bridge_address: address,
+                bridge_username: user.name,
+                bridge_id,
+                client_key: user.clientkey.clone(),
+            },
+        );
+        if file.default_profile.is_none() {
+            file.default_profile = Some(profile_name.clone());
+        }
+        if let Err(e) = file.write() {
+            exit!("Failed to write configuration file", e);
+        }
+        println!("Saved credentials in profile '{}'", profile_name);
+        if let Some(v) = user.clientkey {
+            println!("Client key: {}", v);
+        }
+        return;
+    }
+    // The client key is not read from the environment, so it is printed to stderr instead of
+    // being exported with the other credentials.
+    if let Some(v) = user.clientkey {
+        eprintln!("Client key: {}", v);
+    }
+    let variables = [
+        (crate::config::VAR_BRIDGE_IP, address.to_string()),
+        (crate::config::VAR_BRIDGE_USERNAME, user.name),
+    ];
+    for (name, value) in &variables {
+        match &arg.shell {
+            Some(value::Shell::Fish) => println!("set -x {} '{}'", name, value),
+            Some(value::Shell::Bash) | Some(value::Shell::Zsh) => {
+                println!("export {}='{}'", name, value)
+            }
+            None => println!("{}={}", name, value),
+        }
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub struct GetOptions {
+    /// Prints only the resources that match the filter, e.g. `state.on=true`, `kind~color` or
+    /// `reachable!=true`
+    #[structopt(long, number_of_values = 1)]
+    pub filter: Vec<value::Filter>,
+    /// Sorts the resources by the given field, a leading `-` sorts in descending order
+    #[structopt(long, allow_hyphen_values = true)]
+    pub sort: Option<String>,
+    /// Sets the columns of the table output, e.g. `id,name,state.on`
+    #[structopt(long, use_delimiter = true)]
+    pub columns: Option<Vec<String>>,
+    /// Prints only the given fields, e.g. `name,state.brightness`
+    #[structopt(long, short, use_delimiter = true)]
+    pub fields: Option<Vec<String>>,
+    /// Prints the values that match the query, e.g. `state.on` or `[*].name`
+    #[structopt(long, short)]
+    pub query: Option<value::Query>,
+}
+
+impl GetOptions {
+    pub fn to_output_options(&self) -> output::Options {
+        output::Options {
+            filters: self.filter.iter().map(|v| v.0.clone()).collect(),
+            sort: self.sort.as_deref().map(output::Sort::parse),
+            columns: self.columns.clone(),
+            fields: self.fields.clone(),
+            query: self.query.as_ref().map(|v| v.0.clone()),
+        }
     }
 }
 
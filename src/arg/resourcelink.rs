@@ -1,4 +1,4 @@
-use crate::{output::Resourcelink as OutputResourcelink, util};
+use crate::{arg, output, output::Resourcelink as OutputResourcelink, resolve::Resource, util};
 use huelib::resource::{resourcelink, Modifier};
 use structopt::StructOpt;
 
@@ -16,7 +16,7 @@ pub enum Arg {
 
 #[derive(Debug, StructOpt)]
 pub struct Set {
-    /// Identifier of the resourcelink
+    /// Identifier or name of the resourcelink
     pub id: String,
     /// Sets the name of the resourcelink
     #[structopt(long, short)]
@@ -46,36 +46,41 @@ impl Set {
 }
 
 pub fn set(arg: Set) {
-    let responses = match util::get_bridge().set_resourcelink(&arg.id, &arg.to_modifier()) {
+    let bridge = util::get_bridge();
+    let id = util::resolve_id(&bridge, Resource::Resourcelink, &arg.id);
+    let modifier = arg.to_modifier();
+    let responses = match bridge.set_resourcelink(&id, &modifier) {
         Ok(v) => v,
         Err(e) => exit!("Failed to set resourcelink", e),
     };
-    for i in responses {
-        println!("{}", i);
-    }
+    let mut modifications = output::Modifications::default();
+    modifications.extend(responses, &modifier);
+    util::print_modifications(&modifications);
 }
 
 #[derive(Debug, StructOpt)]
 pub struct Get {
-    /// Identifier of the resourcelink, if ommited all resourcelinks are selected
+    /// Identifier or name of the resourcelink, if ommited all resourcelinks are selected
     id: Option<String>,
+    #[structopt(flatten)]
+    pub options: arg::GetOptions,
 }
 
 pub fn get(arg: Get) {
     let bridge = util::get_bridge();
+    let options = arg.options.to_output_options();
     match arg.id {
-        Some(v) => match bridge.get_resourcelink(&v) {
-            Ok(v) => println!(
-                "{}",
-                serde_json::to_string_pretty(&OutputResourcelink::from(v)).unwrap()
-            ),
-            Err(e) => exit!("Failed to get resourcelinks", e),
-        },
+        Some(v) => {
+            match bridge.get_resourcelink(util::resolve_id(&bridge, Resource::Resourcelink, &v)) {
+                Ok(v) => output::print_with(&OutputResourcelink::from(v), &options),
+                Err(e) => exit!("Failed to get resourcelinks", e),
+            }
+        }
         None => match bridge.get_all_resourcelinks() {
             Ok(v) => {
                 let resourcelinks: Vec<OutputResourcelink> =
                     v.into_iter().map(OutputResourcelink::from).collect();
-                println!("{}", serde_json::to_string_pretty(&resourcelinks).unwrap());
+                output::print_with(&resourcelinks, &options);
             }
             Err(e) => exit!("Failed to get resourcelinks", e),
         },
@@ -129,13 +134,15 @@ pub fn create(arg: Create) {
 
 #[derive(Debug, StructOpt)]
 pub struct Delete {
-    /// Identifier of the resourcelink
+    /// Identifier or exact name of the resourcelink, the case of the name is ignored
     pub id: String,
 }
 
 pub fn delete(arg: Delete) {
-    match util::get_bridge().delete_resourcelink(&arg.id) {
-        Ok(_) => println!("Deleted resourcelink {}", arg.id),
+    let bridge = util::get_bridge();
+    let id = util::resolve_exact_id(&bridge, Resource::Resourcelink, &arg.id);
+    match bridge.delete_resourcelink(&id) {
+        Ok(_) => println!("Deleted resourcelink {}", id),
         Err(e) => exit!("Failed to delete resourcelink", e),
     };
 }
@@ -1,4 +1,4 @@
-use crate::{output::Rule as OutputRule, util};
+use crate::{arg, output, output::Rule as OutputRule, resolve::Resource, util};
 use huelib::resource::{rule, Modifier};
 use structopt::StructOpt;
 
@@ -16,7 +16,7 @@ pub enum Arg {
 
 #[derive(Debug, StructOpt)]
 pub struct Set {
-    /// Identifier of the rule
+    /// Identifier or name of the rule
     pub id: String,
     /// Sets the name of the rule
     #[structopt(long, short)]
@@ -34,35 +34,38 @@ impl Set {
 }
 
 pub fn set(arg: Set) {
-    let responses = match util::get_bridge().set_rule(&arg.id, &arg.to_modifier()) {
+    let bridge = util::get_bridge();
+    let id = util::resolve_id(&bridge, Resource::Rule, &arg.id);
+    let modifier = arg.to_modifier();
+    let responses = match bridge.set_rule(&id, &modifier) {
         Ok(v) => v,
         Err(e) => exit!("Failed to set rule", e),
     };
-    for i in responses {
-        println!("{}", i);
-    }
+    let mut modifications = output::Modifications::default();
+    modifications.extend(responses, &modifier);
+    util::print_modifications(&modifications);
 }
 
 #[derive(Debug, StructOpt)]
 pub struct Get {
-    /// Identifier of the rule, if omitted all rules are selected
+    /// Identifier or name of the rule, if omitted all rules are selected
     pub id: Option<String>,
+    #[structopt(flatten)]
+    pub options: arg::GetOptions,
 }
 
 pub fn get(arg: Get) {
     let bridge = util::get_bridge();
+    let options = arg.options.to_output_options();
     match arg.id {
-        Some(v) => match bridge.get_rule(&v) {
-            Ok(v) => println!(
-                "{}",
-                serde_json::to_string_pretty(&OutputRule::from(v)).unwrap()
-            ),
+        Some(v) => match bridge.get_rule(util::resolve_id(&bridge, Resource::Rule, &v)) {
+            Ok(v) => output::print_with(&OutputRule::from(v), &options),
             Err(e) => exit!("Failed to get rule", e),
         },
         None => match bridge.get_all_rules() {
             Ok(v) => {
                 let rules: Vec<OutputRule> = v.into_iter().map(OutputRule::from).collect();
-                println!("{}", serde_json::to_string_pretty(&rules).unwrap());
+                output::print_with(&rules, &options);
             }
             Err(e) => exit!("Failed to get rules", e),
         },
@@ -90,13 +93,15 @@ pub fn create(arg: Create) {
 
 #[derive(Debug, StructOpt)]
 pub struct Delete {
-    /// Identifier of the rule
+    /// Identifier or exact name of the rule, the case of the name is ignored
     pub id: String,
 }
 
 pub fn delete(arg: Delete) {
-    match util::get_bridge().delete_rule(&arg.id) {
-        Ok(_) => println!("Deleted rule {}", arg.id),
+    let bridge = util::get_bridge();
+    let id = util::resolve_exact_id(&bridge, Resource::Rule, &arg.id);
+    match bridge.delete_rule(&id) {
+        Ok(_) => println!("Deleted rule {}", id),
         Err(e) => exit!("Failed to delete rule", e),
     };
 }
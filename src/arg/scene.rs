@@ -1,4 +1,10 @@
-use crate::{arg::value, output::Scene as OutputScene, util};
+use crate::{
+    arg::{self, value},
+    output,
+    output::Scene as OutputScene,
+    resolve::Resource,
+    util,
+};
 use huelib::resource::{scene, Modifier};
 use structopt::StructOpt;
 
@@ -16,12 +22,12 @@ pub enum Arg {
 
 #[derive(Debug, StructOpt)]
 pub struct Set {
-    /// Identifier of the scene
+    /// Identifier or name of the scene
     pub id: String,
     /// Renames the scene
     #[structopt(long, short)]
     name: Option<String>,
-    /// Sets the identifiers of the lights in this scene
+    /// Sets the identifiers or names of the lights in this scene
     #[structopt(long, short)]
     lights: Option<Vec<String>>,
     /// Stores the light state
@@ -51,36 +57,42 @@ impl Set {
     }
 }
 
-pub fn set(arg: Set) {
-    let responses = match util::get_bridge().set_scene(&arg.id, &arg.to_modifier()) {
+pub fn set(mut arg: Set) {
+    let bridge = util::get_bridge();
+    let id = util::resolve_id(&bridge, Resource::Scene, &arg.id);
+    arg.lights = arg
+        .lights
+        .map(|v| util::select_ids(&bridge, Resource::Light, &v, false));
+    let modifier = arg.to_modifier();
+    let responses = match bridge.set_scene(&id, &modifier) {
         Ok(v) => v,
         Err(e) => exit!("Failed to set scene", e),
     };
-    for i in responses {
-        println!("{}", i);
-    }
+    let mut modifications = output::Modifications::default();
+    modifications.extend(responses, &modifier);
+    util::print_modifications(&modifications);
 }
 
 #[derive(Debug, StructOpt)]
 pub struct Get {
-    /// Identifier of the scene, if omitted all scenes are selected
+    /// Identifier or name of the scene, if omitted all scenes are selected
     pub id: Option<String>,
+    #[structopt(flatten)]
+    pub options: arg::GetOptions,
 }
 
 pub fn get(arg: Get) {
     let bridge = util::get_bridge();
+    let options = arg.options.to_output_options();
     match arg.id {
-        Some(v) => match bridge.get_scene(&v) {
-            Ok(v) => println!(
-                "{}",
-                serde_json::to_string_pretty(&OutputScene::from(v)).unwrap()
-            ),
+        Some(v) => match bridge.get_scene(util::resolve_id(&bridge, Resource::Scene, &v)) {
+            Ok(v) => output::print_with(&OutputScene::from(v), &options),
             Err(e) => exit!("Failed to get scene", e),
         },
         None => match bridge.get_all_scenes() {
             Ok(v) => {
                 let scenes: Vec<OutputScene> = v.into_iter().map(OutputScene::from).collect();
-                println!("{}", serde_json::to_string_pretty(&scenes).unwrap());
+                output::print_with(&scenes, &options);
             }
             Err(e) => exit!("Failed to get scenes", e),
         },
@@ -91,7 +103,7 @@ pub fn get(arg: Get) {
 pub struct Create {
     /// The name of the new scene
     name: String,
-    /// Sets the identifiers of the lights that will be in this scene
+    /// Sets the identifiers or names of the lights that will be in this scene
     #[structopt(long, short)]
     lights: Vec<String>,
     /// Sets the type of the scene
@@ -121,8 +133,10 @@ impl Create {
     }
 }
 
-pub fn create(arg: Create) {
-    match util::get_bridge().create_scene(&arg.to_creator()) {
+pub fn create(mut arg: Create) {
+    let bridge = util::get_bridge();
+    arg.lights = util::select_ids(&bridge, Resource::Light, &arg.lights, false);
+    match bridge.create_scene(&arg.to_creator()) {
         Ok(v) => println!("Created scene {}", v),
         Err(e) => exit!("Failed to create scene", e),
     };
@@ -130,13 +144,15 @@ pub fn create(arg: Create) {
 
 #[derive(Debug, StructOpt)]
 pub struct Delete {
-    /// Identifier of the scene
+    /// Identifier or exact name of the scene, the case of the name is ignored
     pub id: String,
 }
 
 pub fn delete(arg: Delete) {
-    match util::get_bridge().delete_scene(&arg.id) {
-        Ok(_) => println!("Deleted scene {}", arg.id),
+    let bridge = util::get_bridge();
+    let id = util::resolve_exact_id(&bridge, Resource::Scene, &arg.id);
+    match bridge.delete_scene(&id) {
+        Ok(_) => println!("Deleted scene {}", id),
         Err(e) => exit!("Failed to delete scene", e),
     };
 }
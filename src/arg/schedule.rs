@@ -1,4 +1,10 @@
-use crate::{arg::value, output::Schedule as OutputSchedule, util};
+use crate::{
+    arg::{self, value},
+    output,
+    output::Schedule as OutputSchedule,
+    resolve::Resource,
+    util,
+};
 use huelib::resource::{self, schedule, Modifier};
 use structopt::StructOpt;
 
@@ -16,7 +22,7 @@ pub enum Arg {
 
 #[derive(Debug, StructOpt)]
 pub struct Set {
-    /// Identifier of the schedule
+    /// Identifier or name of the schedule
     pub id: String,
     /// Enables the schedule
     #[structopt(long)]
@@ -68,36 +74,39 @@ impl Set {
 }
 
 pub fn set(arg: Set) {
-    let responses = match util::get_bridge().set_schedule(&arg.id, &arg.to_modifier()) {
+    let bridge = util::get_bridge();
+    let id = util::resolve_id(&bridge, Resource::Schedule, &arg.id);
+    let modifier = arg.to_modifier();
+    let responses = match bridge.set_schedule(&id, &modifier) {
         Ok(v) => v,
         Err(e) => exit!("Failed to set schedule", e),
     };
-    for i in responses {
-        println!("{}", i);
-    }
+    let mut modifications = output::Modifications::default();
+    modifications.extend(responses, &modifier);
+    util::print_modifications(&modifications);
 }
 
 #[derive(Debug, StructOpt)]
 pub struct Get {
-    /// Identifier of the schedule, if omitted all schedules are selected
+    /// Identifier or name of the schedule, if omitted all schedules are selected
     pub id: Option<String>,
+    #[structopt(flatten)]
+    pub options: arg::GetOptions,
 }
 
 pub fn get(arg: Get) {
     let bridge = util::get_bridge();
+    let options = arg.options.to_output_options();
     match arg.id {
-        Some(v) => match bridge.get_schedule(&v) {
-            Ok(v) => println!(
-                "{}",
-                serde_json::to_string_pretty(&OutputSchedule::from(v)).unwrap()
-            ),
+        Some(v) => match bridge.get_schedule(util::resolve_id(&bridge, Resource::Schedule, &v)) {
+            Ok(v) => output::print_with(&OutputSchedule::from(v), &options),
             Err(e) => exit!("Failed to get schedule", e),
         },
         None => match bridge.get_all_schedules() {
             Ok(v) => {
                 let schedules: Vec<OutputSchedule> =
                     v.into_iter().map(OutputSchedule::from).collect();
-                println!("{}", serde_json::to_string_pretty(&schedules).unwrap());
+                output::print_with(&schedules, &options);
             }
             Err(e) => exit!("Failed to get schedules", e),
         },
@@ -185,13 +194,15 @@ pub fn create(arg: Create) {
 
 #[derive(Debug, StructOpt)]
 pub struct Delete {
-    /// Identifier of the schedule
+    /// Identifier or exact name of the schedule, the case of the name is ignored
     pub id: String,
 }
 
 pub fn delete(arg: Delete) {
-    match util::get_bridge().delete_schedule(&arg.id) {
-        Ok(_) => println!("Deleted schedule {}", arg.id),
+    let bridge = util::get_bridge();
+    let id = util::resolve_exact_id(&bridge, Resource::Schedule, &arg.id);
+    match bridge.delete_schedule(&id) {
+        Ok(_) => println!("Deleted schedule {}", id),
         Err(e) => exit!("Failed to delete schedule", e),
     };
 }
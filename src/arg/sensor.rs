@@ -1,4 +1,7 @@
-use crate::{output::Scan as OutputScan, output::Sensor as OutputSensor, util};
+use crate::{
+    arg, output, output::Scan as OutputScan, output::Sensor as OutputSensor, resolve::Resource,
+    util,
+};
 use huelib::resource::{sensor, Modifier};
 use structopt::StructOpt;
 
@@ -16,7 +19,7 @@ pub enum Arg {
 
 #[derive(Debug, StructOpt)]
 pub struct Set {
-    /// Identifier of the sensor
+    /// Identifier or name of the sensor
     pub id: String,
     /// Turns the sensor on
     #[structopt(long)]
@@ -67,55 +70,64 @@ impl Set {
 
 pub fn set(arg: Set) {
     let bridge = util::get_bridge();
-    let mut responses = Vec::new();
+    let id = util::resolve_id(&bridge, Resource::Sensor, &arg.id);
+    let mut modifications = output::Modifications::default();
     let state_modifier = arg.to_state_modifier();
     if !state_modifier.is_empty() {
-        responses.extend(match bridge.set_sensor_state(&arg.id, &state_modifier) {
-            Ok(v) => v,
-            Err(e) => exit!("Error occured while modifying the state of the sensor", e),
-        });
+        modifications.extend(
+            match bridge.set_sensor_state(&id, &state_modifier) {
+                Ok(v) => v,
+                Err(e) => exit!("Error occured while modifying the state of the sensor", e),
+            },
+            &state_modifier,
+        );
     }
     let attribute_modifier = arg.to_attribute_modifier();
     if !attribute_modifier.is_empty() {
-        responses.extend(
-            match bridge.set_sensor_attribute(&arg.id, &attribute_modifier) {
+        modifications.extend(
+            match bridge.set_sensor_attribute(&id, &attribute_modifier) {
                 Ok(v) => v,
                 Err(e) => exit!("Error occured while modifying attributes of the sensor", e),
             },
+            &attribute_modifier,
         );
     }
     let config_modifier = arg.to_config_modifier();
     if !config_modifier.is_empty() {
-        responses.extend(match bridge.set_sensor_config(&arg.id, &config_modifier) {
-            Ok(v) => v,
-            Err(e) => exit!("Error occured while modifying the config of the sensor", e),
-        });
-    }
-    for i in responses {
-        println!("{}", i);
+        modifications.extend(
+            match bridge.set_sensor_config(&id, &config_modifier) {
+                Ok(v) => v,
+                Err(e) => exit!("Error occured while modifying the config of the sensor", e),
+            },
+            &config_modifier,
+        );
     }
+    util::print_modifications(&modifications);
 }
 
 #[derive(Debug, StructOpt)]
 pub struct Get {
-    /// Identifier of the sensor, if omitted all sensors are selected
+    /// Identifier or name of the sensor, if omitted all sensors are selected
     pub id: Option<String>,
+    #[structopt(flatten)]
+    pub options: arg::GetOptions,
 }
 
 pub fn get(arg: Get) {
     let bridge = util::get_bridge();
+    let options = arg.options.to_output_options();
     match arg.id {
-        Some(v) => match bridge.get_sensor(&v) {
-            Ok(v) => println!(
-                "{}",
-                serde_json::to_string_pretty(&OutputSensor::from(v)).unwrap()
-            ),
-            Err(e) => exit!("Failed to get sensor", e),
-        },
+        Some(v) => {
+            let id = util::resolve_id(&bridge, Resource::Sensor, &v);
+            match bridge.get_sensor(&id) {
+                Ok(v) => output::print_with(&OutputSensor::from(v), &options),
+                Err(e) => exit!("Failed to get sensor", e),
+            }
+        }
         None => match bridge.get_all_sensors() {
             Ok(v) => {
                 let sensors: Vec<OutputSensor> = v.into_iter().map(OutputSensor::from).collect();
-                println!("{}", serde_json::to_string_pretty(&sensors).unwrap());
+                output::print_with(&sensors, &options);
             }
             Err(e) => exit!("Failed to get sensors", e),
         },
@@ -133,10 +145,7 @@ pub fn search(arg: Search) {
     let bridge = util::get_bridge();
     if arg.get {
         match bridge.get_new_sensors() {
-            Ok(v) => println!(
-                "{}",
-                serde_json::to_string_pretty(&OutputScan::from(v)).unwrap()
-            ),
+            Ok(v) => output::print(&OutputScan::from(v)),
             Err(e) => exit!("Failed to get new sensors", e),
         };
     } else {
@@ -149,13 +158,15 @@ pub fn search(arg: Search) {
 
 #[derive(Debug, StructOpt)]
 pub struct Delete {
-    /// Identifier of the sensor
+    /// Identifier or exact name of the sensor, the case of the name is ignored
     pub id: String,
 }
 
 pub fn delete(arg: Delete) {
-    match util::get_bridge().delete_sensor(&arg.id) {
-        Ok(_) => println!("Deleted sensor {}", arg.id),
+    let bridge = util::get_bridge();
+    let id = util::resolve_exact_id(&bridge, Resource::Sensor, &arg.id);
+    match bridge.delete_sensor(&id) {
+        Ok(_) => println!("Deleted sensor {}", id),
         Err(e) => exit!("Failed to delete sensor", e),
     };
 }
@@ -0,0 +1,119 @@
+use crate::{
+    arg::value,
+    error::{Error, Kind},
+    output,
+    output::User as OutputUser,
+    util,
+};
+use std::io::{self, BufRead, Write};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+pub enum Arg {
+    /// Prints the users that are registered on the bridge
+    List,
+    /// Deletes a user from the bridge
+    Delete(Delete),
+    /// Deletes users that were not used for a given duration
+    Prune(Prune),
+}
+
+pub fn list() {
+    let users: Vec<OutputUser> = match util::get_bridge().get_config() {
+        Ok(v) => v.whitelist.into_iter().map(OutputUser::from).collect(),
+        Err(e) => exit!("Failed to get users", e),
+    };
+    output::print(&users);
+}
+
+#[derive(Debug, StructOpt)]
+pub struct Delete {
+    /// Identifier of the user
+    pub id: String,
+}
+
+pub fn delete(arg: Delete) {
+    if arg.id == util::get_config().bridge_username {
+        Error::new(
+            Kind::InvalidParameter,
+            "The user that is used by huectl can not be deleted",
+        )
+        .exit();
+    }
+    match util::get_bridge().delete_user(&arg.id) {
+        Ok(_) => println!("Deleted user {}", arg.id),
+        Err(e) => exit!("Failed to delete user", e),
+    };
+}
+
+#[derive(Debug, StructOpt)]
+pub struct Prune {
+    /// Deletes users that were not used for this duration (e.g. `180d`)
+    #[structopt(long, short)]
+    pub unused_for: value::Duration,
+    /// Prints the users that would be deleted without deleting them
+    #[structopt(long, short = "n")]
+    pub dry_run: bool,
+    /// Deletes the users without asking for confirmation
+    #[structopt(long, short)]
+    pub yes: bool,
+}
+
+pub fn prune(arg: Prune) {
+    let username = util::get_config().bridge_username;
+    let bridge = util::get_bridge();
+    let users = match bridge.get_config() {
+        Ok(v) => v.whitelist,
+        Err(e) => exit!("Failed to get users", e),
+    };
+    let unused_for = match chrono::Duration::from_std(arg.unused_for.0) {
+        Ok(v) => v,
+        Err(_) => Error::new(Kind::InvalidParameter, "The duration is too long").exit(),
+    };
+    let now = chrono::Utc::now().naive_utc();
+    let users: Vec<OutputUser> = users
+        .into_iter()
+        .filter(|v| v.id != username && now.signed_duration_since(v.last_use_date) > unused_for)
+        .map(OutputUser::from)
+        .collect();
+    if users.is_empty() {
+        println!("No users to delete");
+        return;
+    }
+    if arg.dry_run {
+        output::print(&users);
+        return;
+    }
+    if !arg.yes && !confirm(&users) {
+        exit!("Aborted");
+    }
+    // The remaining users are still deleted if a deletion fails, the command exits with the
+    // error of the first failed deletion afterwards.
+    let mut failed = Vec::new();
+    let mut first_error = None;
+    for user in users {
+        match bridge.delete_user(&user.id) {
+            Ok(_) => println!("Deleted user {}", user.id),
+            Err(e) => {
+                first_error.get_or_insert(e);
+                failed.push(user.id);
+            }
+        };
+    }
+    if let Some(e) = first_error {
+        exit!(format!("Failed to delete users {}", failed.join(", ")), e);
+    }
+}
+
+fn confirm(users: &[OutputUser]) -> bool {
+    for user in users {
+        eprintln!("{} ({})", user.id, user.name);
+    }
+    eprint!("Delete {} users? [y/N] ", users.len());
+    let _ = io::stderr().flush();
+    let mut answer = String::new();
+    match io::stdin().lock().read_line(&mut answer) {
+        Ok(_) => answer.trim().eq_ignore_ascii_case("y"),
+        Err(_) => false,
+    }
+}
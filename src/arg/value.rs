@@ -1,4 +1,4 @@
-use crate::arg;
+use crate::{arg, error, output};
 use huelib::resource::{self, ModifierType};
 use huelib::Color;
 
@@ -37,7 +37,7 @@ impl std::str::FromStr for Brightness {
         }
         Ok(Self(
             modifier_type,
-            (value as f32 * (u8::max_value() as f32 / max_value as f32)) as u8,
+            (value as f32 * (u8::MAX as f32 / max_value as f32)) as u8,
         ))
     }
 }
@@ -49,7 +49,7 @@ impl std::str::FromStr for Hue {
     type Err = arg::ParseError;
     fn from_str(s: &str) -> Result<Self, arg::ParseError> {
         let (modifier_type, value) =
-            parse_with_suffix::<u16>(s, arg::ParseError::from_integer_value(&u16::max_value()))?;
+            parse_with_suffix::<u16>(s, arg::ParseError::from_integer_value(&u16::MAX))?;
         Ok(Self(modifier_type, value))
     }
 }
@@ -68,7 +68,7 @@ impl std::str::FromStr for Saturation {
         }
         Ok(Self(
             modifier_type,
-            (value as f32 * (u8::max_value() as f32 / max_value as f32)) as u8,
+            (value as f32 * (u8::MAX as f32 / max_value as f32)) as u8,
         ))
     }
 }
@@ -85,15 +85,161 @@ impl std::str::FromStr for ColorHex {
     }
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ColorTemperatureUnit {
+    Mired,
+    Kelvin,
+}
+
+/// Color temperature in mireds or Kelvin, e.g. `370`, `370mired`, `2700K` or `+500K`.
 #[derive(Debug)]
-pub struct ColorTemperature(pub ModifierType, pub u16);
+pub struct ColorTemperature(pub ModifierType, pub u32, pub ColorTemperatureUnit);
+
+impl ColorTemperature {
+    /// Returns the color temperature in mireds that is set on the given lights.
+    ///
+    /// Relative changes in mireds are sent to the bridge unchanged, as the bridge applies them to
+    /// the current color temperature of every light and stops at the limits of its range, see
+    /// `exceeded_range`. Relative changes in Kelvin are applied to the current color temperature of
+    /// the first light that has one.
+    ///
+    /// Other values must be in the range that all lights support, if `clamp` is set they are
+    /// clamped to the range instead.
+    pub fn to_mired(
+        &self,
+        lights: &[resource::light::Light],
+        clamp: bool,
+    ) -> Result<(ModifierType, u16), String> {
+        let Self(modifier_type, value, unit) = self;
+        let target_kelvin = match (unit, modifier_type) {
+            (ColorTemperatureUnit::Mired, ModifierType::Override) => None,
+            (ColorTemperatureUnit::Mired, _) => return Ok((*modifier_type, *value as u16)),
+            (ColorTemperatureUnit::Kelvin, ModifierType::Override) => Some(*value),
+            (ColorTemperatureUnit::Kelvin, _) => {
+                let current = match lights
+                    .iter()
+                    .find_map(|v| v.state.color_temperature)
+                    .filter(|v| *v > 0)
+                {
+                    Some(v) => kelvin(v),
+                    None => {
+                        return Err(
+                            "The current color temperature is unknown, so it can not be \
+                                    changed in Kelvin"
+                                .to_owned(),
+                        )
+                    }
+                };
+                match modifier_type {
+                    ModifierType::Increment => Some(current.saturating_add(*value)),
+                    _ => Some(current.saturating_sub(*value)),
+                }
+            }
+        };
+        let ranges: Vec<(u16, u16)> = lights
+            .iter()
+            .filter_map(|v| v.capabilities.control.color_temperature.as_ref())
+            .map(|v| (v.min as u16, v.max as u16))
+            .collect();
+        let min = ranges.iter().map(|v| v.0).max();
+        let max = ranges.iter().map(|v| v.1).min();
+        let target = target_kelvin.map_or(*value, mired).min(u16::MAX as u32) as u16;
+        match (min, max) {
+            (Some(min), Some(max)) if min <= max && (target < min || target > max) => {
+                if clamp {
+                    Ok((ModifierType::Override, target.clamp(min, max)))
+                } else {
+                    Err(format!(
+                        "{}K ({} mired) is outside of the supported range from {}K to {}K ({} to \
+                         {} mired), add --clamp to use the closest supported value",
+                        target_kelvin.unwrap_or_else(|| kelvin(target)),
+                        target,
+                        kelvin(max),
+                        kelvin(min),
+                        min,
+                        max
+                    ))
+                }
+            }
+            _ => Ok((ModifierType::Override, target)),
+        }
+    }
+
+    /// Returns a warning if a relative change in mireds exceeds the range of a light, the bridge
+    /// stops at the limit of the range of such lights.
+    pub fn exceeded_range(&self, lights: &[resource::light::Light]) -> Option<String> {
+        let Self(modifier_type, value, unit) = self;
+        if *unit != ColorTemperatureUnit::Mired || *modifier_type == ModifierType::Override {
+            return None;
+        }
+        let ids: Vec<&str> = lights
+            .iter()
+            .filter(|light| {
+                let current = light.state.color_temperature.filter(|v| *v > 0);
+                let range = light.capabilities.control.color_temperature.as_ref();
+                match (current, range) {
+                    (Some(current), Some(range)) => match modifier_type {
+                        ModifierType::Increment => current as u32 + value > range.max as u32,
+                        _ => (current as u32) < range.min as u32 + value,
+                    },
+                    _ => false,
+                }
+            })
+            .map(|v| v.id.as_str())
+            .collect();
+        match ids.as_slice() {
+            [] => None,
+            [id] => Some(format!(
+                "The change exceeds the color temperature range of light {}, the bridge stops at \
+                 the limit of the range",
+                id
+            )),
+            _ => Some(format!(
+                "The change exceeds the color temperature range of lights {}, the bridge stops at \
+                 the limits of their ranges",
+                ids.join(", ")
+            )),
+        }
+    }
+}
+
+/// Converts a color temperature from Kelvin into mireds.
+fn mired(kelvin: u32) -> u32 {
+    (1_000_000.0 / kelvin.max(1) as f32).round() as u32
+}
+
+/// Converts a color temperature from mireds into Kelvin.
+fn kelvin(mired: u16) -> u32 {
+    (1_000_000.0 / mired.max(1) as f32).round() as u32
+}
 
 impl std::str::FromStr for ColorTemperature {
     type Err = arg::ParseError;
     fn from_str(s: &str) -> Result<Self, arg::ParseError> {
-        let (modifier_type, value) =
-            parse_with_suffix::<u16>(s, arg::ParseError::from_integer_value(&u16::max_value()))?;
-        Ok(Self(modifier_type, value))
+        let error = arg::ParseError::new(
+            "The value must be a color temperature in Kelvin (e.g. `2700K`) or mireds (e.g. \
+             `370mired` or `370`) and can have '-' or '+' as prefix.",
+        );
+        let value = s.trim().to_lowercase();
+        let (number, unit) = if let Some(v) = value
+            .strip_suffix("mireds")
+            .or_else(|| value.strip_suffix("mired"))
+        {
+            (v, ColorTemperatureUnit::Mired)
+        } else if let Some(v) = value.strip_suffix('k') {
+            (v, ColorTemperatureUnit::Kelvin)
+        } else {
+            (value.as_str(), ColorTemperatureUnit::Mired)
+        };
+        let (modifier_type, value) = parse_with_suffix::<u32>(number.trim_end(), error.clone())?;
+        let is_valid = match unit {
+            ColorTemperatureUnit::Mired => value <= u16::MAX as u32,
+            ColorTemperatureUnit::Kelvin => value > 0 || modifier_type != ModifierType::Override,
+        };
+        if !is_valid {
+            return Err(error);
+        }
+        Ok(Self(modifier_type, value, unit))
     }
 }
 
@@ -309,3 +455,258 @@ impl std::str::FromStr for ScheduleRequestType {
         Ok(Self(value))
     }
 }
+
+#[derive(Debug)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    pub fn variants() -> &'static [&'static str] {
+        &["bash", "zsh", "fish"]
+    }
+}
+
+impl std::str::FromStr for Shell {
+    type Err = arg::ParseError;
+    fn from_str(s: &str) -> Result<Self, arg::ParseError> {
+        let value = match s.to_lowercase().as_ref() {
+            "bash" => Self::Bash,
+            "zsh" => Self::Zsh,
+            "fish" => Self::Fish,
+            _ => return Err(arg::ParseError::new("Invalid value for shell")),
+        };
+        Ok(value)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Duration(pub std::time::Duration);
+
+impl std::str::FromStr for Duration {
+    type Err = arg::ParseError;
+    fn from_str(s: &str) -> Result<Self, arg::ParseError> {
+        let error = arg::ParseError::new(
+            "The value must be an integer with 's', 'm', 'h' or 'd' as optional suffix.",
+        );
+        let (value, factor) = match s.chars().last() {
+            Some('s') => (&s[..s.len() - 1], 1),
+            Some('m') => (&s[..s.len() - 1], 60),
+            Some('h') => (&s[..s.len() - 1], 60 * 60),
+            Some('d') => (&s[..s.len() - 1], 60 * 60 * 24),
+            Some(_) => (s, 1),
+            None => return Err(error),
+        };
+        let value: u64 = value.parse().map_err(|_| error.clone())?;
+        let seconds = value.checked_mul(factor).ok_or(error)?;
+        Ok(Self(std::time::Duration::from_secs(seconds)))
+    }
+}
+
+#[derive(Debug)]
+pub struct DiscoverMethod(pub crate::discover::Method);
+
+impl DiscoverMethod {
+    pub fn variants() -> &'static [&'static str] {
+        &["nupnp", "mdns", "ssdp"]
+    }
+}
+
+impl std::str::FromStr for DiscoverMethod {
+    type Err = arg::ParseError;
+    fn from_str(s: &str) -> Result<Self, arg::ParseError> {
+        use crate::discover::Method;
+        let value = match s.to_lowercase().as_ref() {
+            "nupnp" => Method::Nupnp,
+            "mdns" => Method::Mdns,
+            "ssdp" => Method::Ssdp,
+            _ => return Err(arg::ParseError::new("Invalid value for method")),
+        };
+        Ok(Self(value))
+    }
+}
+
+#[derive(Debug)]
+pub struct OutputFormat(pub output::Format);
+
+impl OutputFormat {
+    pub fn variants() -> &'static [&'static str] {
+        &["json", "json-compact", "ndjson", "yaml", "table", "plain"]
+    }
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = arg::ParseError;
+    fn from_str(s: &str) -> Result<Self, arg::ParseError> {
+        use output::Format;
+        let value = match s.to_lowercase().as_ref() {
+            "json" => Format::Json,
+            "json-compact" => Format::JsonCompact,
+            "ndjson" => Format::Ndjson,
+            "yaml" => Format::Yaml,
+            "table" => Format::Table,
+            "plain" => Format::Plain,
+            _ => return Err(arg::ParseError::new("Invalid value for output")),
+        };
+        Ok(Self(value))
+    }
+}
+
+#[derive(Debug)]
+pub struct ErrorFormat(pub error::Format);
+
+impl ErrorFormat {
+    pub fn variants() -> &'static [&'static str] {
+        &["text", "json"]
+    }
+}
+
+impl std::str::FromStr for ErrorFormat {
+    type Err = arg::ParseError;
+    fn from_str(s: &str) -> Result<Self, arg::ParseError> {
+        let value = match s.to_lowercase().as_ref() {
+            "text" => error::Format::Text,
+            "json" => error::Format::Json,
+            _ => return Err(arg::ParseError::new("Invalid value for error format")),
+        };
+        Ok(Self(value))
+    }
+}
+
+#[derive(Debug)]
+pub struct Query(pub output::Query);
+
+impl std::str::FromStr for Query {
+    type Err = arg::ParseError;
+    fn from_str(s: &str) -> Result<Self, arg::ParseError> {
+        Ok(Self(
+            output::Query::parse(s).map_err(|e| arg::ParseError::new(&e))?,
+        ))
+    }
+}
+
+#[derive(Debug)]
+pub struct Filter(pub output::Filter);
+
+impl std::str::FromStr for Filter {
+    type Err = arg::ParseError;
+    fn from_str(s: &str) -> Result<Self, arg::ParseError> {
+        Ok(Self(
+            output::Filter::parse(s).map_err(|e| arg::ParseError::new(&e))?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use resource::light::Light;
+
+    fn light(id: &str, color_temperature: Option<u16>, range: Option<(u16, u16)>) -> Light {
+        let mut light: Light = serde_json::from_value(serde_json::json!({
+            "name": "Light",
+            "type": "Extended color light",
+            "modelid": "LCT015",
+            "uniqueid": "00:17:88:01:00:00:00:00-0b",
+            "swversion": "1.50.2",
+            "swupdate": { "state": "noupdates", "lastinstall": null },
+            "state": { "on": true, "ct": color_temperature, "reachable": true },
+            "config": { "archetype": "sultanbulb", "function": "mixed", "direction": "omnidirectional" },
+            "capabilities": {
+                "certified": true,
+                "control": { "ct": range.map(|(min, max)| serde_json::json!({ "min": min, "max": max })) },
+                "streaming": { "renderer": true, "proxy": true }
+            }
+        }))
+        .unwrap();
+        light.id = id.to_owned();
+        light
+    }
+
+    fn parse(value: &str) -> (ModifierType, u32, ColorTemperatureUnit) {
+        let ColorTemperature(modifier_type, value, unit) = value.parse().unwrap();
+        (modifier_type, value, unit)
+    }
+
+    fn parse_to_mired(
+        value: &str,
+        lights: &[Light],
+        clamp: bool,
+    ) -> Result<(ModifierType, u16), String> {
+        value
+            .parse::<ColorTemperature>()
+            .unwrap()
+            .to_mired(lights, clamp)
+    }
+
+    #[test]
+    fn parse_color_temperature() {
+        use ColorTemperatureUnit::{Kelvin, Mired};
+        assert_eq!(parse("2700K"), (ModifierType::Override, 2700, Kelvin));
+        assert_eq!(parse("4000k"), (ModifierType::Override, 4000, Kelvin));
+        assert_eq!(parse("370mired"), (ModifierType::Override, 370, Mired));
+        assert_eq!(parse("370 mireds"), (ModifierType::Override, 370, Mired));
+        assert_eq!(parse("370"), (ModifierType::Override, 370, Mired));
+        assert_eq!(parse("+500K"), (ModifierType::Increment, 500, Kelvin));
+        assert_eq!(parse("-50"), (ModifierType::Decrement, 50, Mired));
+        assert_eq!(
+            parse("+4294967295K"),
+            (ModifierType::Increment, u32::MAX, Kelvin)
+        );
+        for value in &["", "K", "0K", "65536", "2700X", "+-5", "4294967296K"] {
+            assert!(value.parse::<ColorTemperature>().is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn color_temperature_to_mired() {
+        let lights = [light("1", Some(250), Some((153, 500)))];
+        let to_mired = |value: &str, clamp| parse_to_mired(value, &lights, clamp);
+        assert_eq!(to_mired("2700K", false), Ok((ModifierType::Override, 370)));
+        assert_eq!(to_mired("370", false), Ok((ModifierType::Override, 370)));
+        assert_eq!(to_mired("+1000K", false), Ok((ModifierType::Override, 200)));
+        assert_eq!(to_mired("-1000K", false), Ok((ModifierType::Override, 333)));
+        assert!(to_mired("10000K", false).is_err());
+        assert_eq!(to_mired("10000K", true), Ok((ModifierType::Override, 153)));
+        assert_eq!(to_mired("600", true), Ok((ModifierType::Override, 500)));
+        assert!(to_mired("+4294967295K", false).is_err());
+        assert_eq!(
+            to_mired("+4294967295K", true),
+            Ok((ModifierType::Override, 153))
+        );
+        assert_eq!(to_mired("-5000K", true), Ok((ModifierType::Override, 500)));
+    }
+
+    #[test]
+    fn relative_mired_is_sent_unchanged() {
+        let lights = [
+            light("1", Some(200), Some((153, 500))),
+            light("2", Some(450), Some((153, 454))),
+        ];
+        assert_eq!(
+            parse_to_mired("+50", &lights, false),
+            Ok((ModifierType::Increment, 50))
+        );
+        assert_eq!(
+            parse_to_mired("-100", &[], false),
+            Ok((ModifierType::Decrement, 100))
+        );
+        let exceeded_range = |value: &str| {
+            let value: ColorTemperature = value.parse().unwrap();
+            value.exceeded_range(&lights)
+        };
+        assert!(exceeded_range("+4").is_none());
+        assert!(exceeded_range("+5").unwrap().contains("light 2,"));
+        assert!(exceeded_range("-50").unwrap().contains("light 1,"));
+        assert!(exceeded_range("+400").unwrap().contains("lights 1, 2,"));
+        assert!(exceeded_range("300").is_none());
+    }
+
+    #[test]
+    fn relative_kelvin_requires_current_color_temperature() {
+        let lights = [light("1", None, Some((153, 500)))];
+        assert!(parse_to_mired("+500K", &lights, false).is_err());
+    }
+}
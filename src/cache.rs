@@ -0,0 +1,206 @@
+use crate::{
+    config,
+    resolve::{Candidate, Resource},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Resources whose identifiers and names are cached.
+pub const RESOURCES: [Resource; 4] = [
+    Resource::Light,
+    Resource::Group,
+    Resource::Scene,
+    Resource::Sensor,
+];
+
+static OPTIONS: OnceLock<Options> = OnceLock::new();
+
+/// Cache of a command that modifies resources, it is read when the command starts.
+static SNAPSHOT: OnceLock<File> = OnceLock::new();
+
+/// Options from the command line that control the cache.
+#[derive(Debug, Default)]
+pub struct Options {
+    /// Whether cached resources are used, commands that delete resources never use them so that
+    /// names can not resolve to a resource that was renamed elsewhere.
+    pub enabled: bool,
+    /// Whether the command modifies resources.
+    pub modifies: bool,
+}
+
+/// Sets the command line options, must be called before resources are requested.
+///
+/// If the command modifies resources, the cache file is removed immediately so that it is
+/// invalidated even if the command fails. Resources that are resolved before the modification are
+/// still taken from the previous cache, but the cache is not written until the next command.
+pub fn init(options: Options) {
+    if options.modifies {
+        if let Ok((path, ttl)) = location() {
+            if options.enabled {
+                let _ = SNAPSHOT.set(File::read(&path).unwrap_or_default().fresh(ttl));
+            }
+            let _ = fs::remove_file(path);
+        }
+    }
+    let _ = OPTIONS.set(options);
+}
+
+fn options() -> &'static Options {
+    OPTIONS.get_or_init(Options::default)
+}
+
+/// Returns the cached identifiers and names of resources, or requests them and updates the
+/// cache if they are missing or expired.
+pub fn candidates<F, E>(resource: Resource, request: F) -> Result<Vec<Candidate>, E>
+where
+    F: FnOnce() -> Result<Vec<Candidate>, E>,
+{
+    let options = options();
+    if !options.enabled || !RESOURCES.contains(&resource) {
+        return request();
+    }
+    if options.modifies {
+        return match SNAPSHOT.get().and_then(|v| v.get(resource)) {
+            Some(v) => Ok(v),
+            None => request(),
+        };
+    }
+    let (path, ttl) = match location() {
+        Ok(v) => v,
+        Err(_) => return request(),
+    };
+    let mut file = File::read(&path).unwrap_or_default().fresh(ttl);
+    if let Some(v) = file.get(resource) {
+        return Ok(v);
+    }
+    let candidates = request()?;
+    if ttl > 0 {
+        file.insert(resource, candidates.clone());
+        // The cache only speeds up commands, so failing to write it is not an error.
+        let _ = file.write(&path);
+    }
+    Ok(candidates)
+}
+
+/// Removes the cache of the bridge of the current configuration.
+pub fn clear() -> Result<(), Error> {
+    let (path, _) = location()?;
+    match fs::remove_file(&path) {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(Error::Io(path, e)),
+    }
+}
+
+/// Returns the path of the cache file and the time to live in seconds.
+///
+/// The cache file is named after the identifier of the bridge that is pinned in the profile, or
+/// after the address of the bridge if no identifier is pinned.
+fn location() -> Result<(PathBuf, u64), Error> {
+    let config = config::get().map_err(Error::Config)?;
+    let name = match &config.bridge_id {
+        Some(v) => v.to_lowercase(),
+        None => config
+            .bridge_address
+            .to_string()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect(),
+    };
+    let path = match dirs::cache_dir() {
+        Some(v) => v.join("huectl").join(format!("{}.json", name)),
+        None => return Err(Error::CacheDir),
+    };
+    Ok((path, config.cache_ttl.as_secs()))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|v| v.as_secs())
+        .unwrap_or_default()
+}
+
+/// Cached resources of a bridge.
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct File {
+    #[serde(flatten)]
+    entries: HashMap<String, Entry>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Entry {
+    /// Seconds since the Unix epoch when the resources were requested.
+    updated: u64,
+    resources: Vec<Candidate>,
+}
+
+impl File {
+    fn read(path: &Path) -> Result<Self, Error> {
+        let content = match fs::read_to_string(path) {
+            Ok(v) => v,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(Error::Io(path.to_owned(), e)),
+        };
+        serde_json::from_str(&content).map_err(|e| Error::Parse(path.to_owned(), e))
+    }
+
+    fn write(&self, path: &Path) -> Result<(), Error> {
+        if let Some(v) = path.parent() {
+            fs::create_dir_all(v).map_err(|e| Error::Io(v.to_owned(), e))?;
+        }
+        let content = serde_json::to_string(self).map_err(Error::Serialize)?;
+        fs::write(path, content).map_err(|e| Error::Io(path.to_owned(), e))
+    }
+
+    /// Removes the entries that are older than the time to live.
+    fn fresh(mut self, ttl: u64) -> Self {
+        let now = now();
+        self.entries
+            .retain(|_, v| v.updated <= now && now - v.updated < ttl);
+        self
+    }
+
+    fn get(&self, resource: Resource) -> Option<Vec<Candidate>> {
+        self.entries
+            .get(&resource.to_string())
+            .map(|v| v.resources.clone())
+    }
+
+    fn insert(&mut self, resource: Resource, resources: Vec<Candidate>) {
+        let entry = Entry {
+            updated: now(),
+            resources,
+        };
+        self.entries.insert(resource.to_string(), entry);
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Config(config::Error),
+    CacheDir,
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, serde_json::Error),
+    Serialize(serde_json::Error),
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Self::Config(e) => write!(f, "{}", e),
+            Self::CacheDir => write!(f, "Failed to find the cache directory"),
+            Self::Io(path, e) => write!(f, "Failed to access '{}': {}", path.display(), e),
+            Self::Parse(path, e) => write!(f, "Failed to parse '{}': {}", path.display(), e),
+            Self::Serialize(e) => write!(f, "Failed to serialize cache: {}", e),
+        }
+    }
+}
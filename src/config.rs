@@ -1,17 +1,190 @@
+use crate::address::Address;
 use envconfig::Envconfig;
-use std::net::IpAddr;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt, fs, io, path::PathBuf, sync::OnceLock, time::Duration};
 
 pub const VAR_BRIDGE_IP: &str = "HUECTL_BRIDGE_IP";
 pub const VAR_BRIDGE_USERNAME: &str = "HUECTL_BRIDGE_USERNAME";
+pub const VAR_PROFILE: &str = "HUECTL_PROFILE";
 
-pub fn get() -> Result<Config, envconfig::Error> {
-    Config::init()
+/// Seconds after which cached resources are requested again if no other value is configured.
+const DEFAULT_CACHE_TTL: u64 = 300;
+
+static OPTIONS: OnceLock<Options> = OnceLock::new();
+
+/// Options from the command line that take precedence over the configuration file and the
+/// environment variables.
+#[derive(Debug, Default)]
+pub struct Options {
+    pub profile: Option<String>,
+    pub bridge_address: Option<Address>,
 }
 
-#[derive(Debug, Envconfig)]
+/// Sets the command line options, must be called before the configuration is requested.
+pub fn init(options: Options) {
+    let _ = OPTIONS.set(options);
+}
+
+fn options() -> &'static Options {
+    OPTIONS.get_or_init(Options::default)
+}
+
+/// Returns the configuration by layering the command line options over the environment
+/// variables over the selected profile of the configuration file.
+pub fn get() -> Result<Config, Error> {
+    let options = options();
+    let env = Env::init().map_err(Error::Env)?;
+    let file = File::read()?;
+    let profile_name = options
+        .profile
+        .clone()
+        .or_else(|| file.default_profile.clone());
+    let profile = match &profile_name {
+        Some(v) => match file.profiles.get(v) {
+            Some(v) => Some(v.clone()),
+            None => return Err(Error::ProfileNotFound(v.clone())),
+        },
+        None => None,
+    };
+    let bridge_address_override = options.bridge_address.clone().or(env.bridge_address);
+    let bridge_address = bridge_address_override
+        .clone()
+        .or_else(|| profile.as_ref().map(|v| v.bridge_address.clone()))
+        .ok_or(Error::MissingValue(VAR_BRIDGE_IP))?;
+    let bridge_id = profile.as_ref().and_then(|v| v.bridge_id.clone());
+    let bridge_username = env
+        .bridge_username
+        .or_else(|| profile.map(|v| v.bridge_username))
+        .ok_or(Error::MissingValue(VAR_BRIDGE_USERNAME))?;
+    let cache_ttl = env
+        .cache_ttl
+        .or(file.cache_ttl)
+        .unwrap_or(DEFAULT_CACHE_TTL);
+    Ok(Config {
+        profile: profile_name.clone(),
+        bridge_address,
+        bridge_username,
+        bridge_id,
+        bridge_address_from_profile: profile_name.is_some() && bridge_address_override.is_none(),
+        cache_ttl: Duration::from_secs(cache_ttl),
+    })
+}
+
+/// Modifies a profile of the configuration file and writes the file.
+pub fn update_profile<F>(name: &str, f: F) -> Result<(), Error>
+where
+    F: FnOnce(&mut Profile),
+{
+    let mut file = File::read()?;
+    match file.profiles.get_mut(name) {
+        Some(v) => f(v),
+        None => return Err(Error::ProfileNotFound(name.to_owned())),
+    }
+    file.write()
+}
+
+#[derive(Debug)]
 pub struct Config {
+    pub profile: Option<String>,
+    pub bridge_address: Address,
+    pub bridge_username: String,
+    /// Identifier of the bridge that is pinned in the profile.
+    pub bridge_id: Option<String>,
+    /// Whether the address of the bridge is taken from the profile.
+    pub bridge_address_from_profile: bool,
+    /// Time after which cached resources are requested again.
+    pub cache_ttl: Duration,
+}
+
+#[derive(Debug, Envconfig)]
+struct Env {
     #[envconfig(from = "HUECTL_BRIDGE_IP")]
-    pub bridge_ip: IpAddr,
+    pub bridge_address: Option<Address>,
     #[envconfig(from = "HUECTL_BRIDGE_USERNAME")]
+    pub bridge_username: Option<String>,
+    #[envconfig(from = "HUECTL_CACHE_TTL")]
+    pub cache_ttl: Option<u64>,
+}
+
+/// The configuration file containing the profiles of the bridges.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct File {
+    pub default_profile: Option<String>,
+    /// Seconds after which cached resources are requested again, `0` disables the cache.
+    pub cache_ttl: Option<u64>,
+    #[serde(default)]
+    pub profiles: HashMap<String, Profile>,
+}
+
+impl File {
+    /// Returns the path of the configuration file.
+    pub fn path() -> Result<PathBuf, Error> {
+        match dirs::config_dir() {
+            Some(v) => Ok(v.join("huectl").join("config.toml")),
+            None => Err(Error::ConfigDir),
+        }
+    }
+
+    /// Reads the configuration file, if the file does not exist an empty configuration is
+    /// returned.
+    pub fn read() -> Result<Self, Error> {
+        let path = Self::path()?;
+        let content = match fs::read_to_string(&path) {
+            Ok(v) => v,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(Error::Io(path, e)),
+        };
+        toml::from_str(&content).map_err(|e| Error::Parse(path, e))
+    }
+
+    /// Writes the configuration file, missing parent directories are created.
+    pub fn write(&self) -> Result<(), Error> {
+        let path = Self::path()?;
+        if let Some(v) = path.parent() {
+            fs::create_dir_all(v).map_err(|e| Error::Io(v.to_owned(), e))?;
+        }
+        let content = toml::to_string_pretty(self).map_err(Error::Serialize)?;
+        fs::write(&path, content).map_err(|e| Error::Io(path, e))
+    }
+}
+
+/// Connection details of a bridge.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Profile {
+    #[serde(alias = "bridge_ip")]
+    pub bridge_address: Address,
     pub bridge_username: String,
+    pub bridge_id: Option<String>,
+    pub client_key: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Env(envconfig::Error),
+    ConfigDir,
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, toml::de::Error),
+    Serialize(toml::ser::Error),
+    ProfileNotFound(String),
+    MissingValue(&'static str),
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Self::Env(e) => write!(f, "Invalid environment variable: {}", e),
+            Self::ConfigDir => write!(f, "Failed to find the configuration directory"),
+            Self::Io(path, e) => write!(f, "Failed to access '{}': {}", path.display(), e),
+            Self::Parse(path, e) => write!(f, "Failed to parse '{}': {}", path.display(), e),
+            Self::Serialize(e) => write!(f, "Failed to serialize configuration: {}", e),
+            Self::ProfileNotFound(v) => write!(f, "Profile '{}' does not exist", v),
+            Self::MissingValue(v) => write!(
+                f,
+                "No value for {} in the environment or the configuration file",
+                v
+            ),
+        }
+    }
 }
@@ -0,0 +1,153 @@
+use crate::address::Address;
+use serde::Deserialize;
+use std::{
+    fmt, io,
+    net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket},
+    time::{Duration, Instant},
+};
+
+const MDNS_ADDRESS: (Ipv4Addr, u16) = (Ipv4Addr::new(224, 0, 0, 251), 5353);
+const MDNS_SERVICE: &str = "_hue._tcp.local";
+const SSDP_ADDRESS: (Ipv4Addr, u16) = (Ipv4Addr::new(239, 255, 255, 250), 1900);
+
+/// Method that is used to discover bridges.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Method {
+    /// Asks the N-UPnP endpoint of meethue.com, requires an internet connection.
+    Nupnp,
+    /// Queries the `_hue._tcp` service with multicast DNS in the local network.
+    Mdns,
+    /// Sends a SSDP search request in the local network.
+    Ssdp,
+}
+
+/// Discovers bridges with the given method, the local methods wait for responses until the
+/// timeout has elapsed.
+pub fn discover(method: Method, timeout: Duration) -> Result<Vec<IpAddr>, Error> {
+    let mut ip_addresses = match method {
+        Method::Nupnp => huelib::bridge::discover().map_err(Error::Nupnp)?,
+        Method::Mdns => mdns(timeout).map_err(Error::Io)?,
+        Method::Ssdp => ssdp(timeout).map_err(Error::Io)?,
+    };
+    ip_addresses.sort();
+    ip_addresses.dedup();
+    Ok(ip_addresses)
+}
+
+fn mdns(timeout: Duration) -> io::Result<Vec<IpAddr>> {
+    // Header with one question and no other records.
+    let mut query = vec![0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0];
+    for label in MDNS_SERVICE.split('.') {
+        query.push(label.len() as u8);
+        query.extend(label.as_bytes());
+    }
+    // Terminating label, type PTR and class IN with the unicast response bit set.
+    query.extend(&[0, 0, 12, 0x80, 1]);
+    let marker = MDNS_SERVICE
+        .split('.')
+        .next()
+        .unwrap_or_default()
+        .as_bytes();
+    receive(&query, MDNS_ADDRESS.into(), timeout, |packet| {
+        // Only responses that mention the hue service are accepted.
+        packet.len() > 2 && packet[2] & 0x80 != 0 && contains(packet, marker)
+    })
+}
+
+fn ssdp(timeout: Duration) -> io::Result<Vec<IpAddr>> {
+    let request = format!(
+        "M-SEARCH * HTTP/1.1\r\nHOST: {}:{}\r\nMAN: \"ssdp:discover\"\r\nMX: {}\r\nST: ssdp:all\r\n\r\n",
+        SSDP_ADDRESS.0,
+        SSDP_ADDRESS.1,
+        timeout.as_secs().max(1)
+    );
+    receive(request.as_bytes(), SSDP_ADDRESS.into(), timeout, |packet| {
+        contains(packet, b"IpBridge") || contains(packet, b"hue-bridgeid")
+    })
+}
+
+fn receive<F>(
+    request: &[u8],
+    address: SocketAddr,
+    timeout: Duration,
+    accept: F,
+) -> io::Result<Vec<IpAddr>>
+where
+    F: Fn(&[u8]) -> bool,
+{
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+    socket.send_to(request, address)?;
+    let deadline = Instant::now() + timeout;
+    let mut ip_addresses = Vec::new();
+    let mut buffer = [0; 4096];
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            break;
+        }
+        socket.set_read_timeout(Some(deadline - now))?;
+        match socket.recv_from(&mut buffer) {
+            Ok((length, source)) => {
+                if accept(&buffer[..length]) {
+                    ip_addresses.push(source.ip());
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+            Err(e) if e.kind() == io::ErrorKind::TimedOut => break,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(ip_addresses)
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|v| v == needle)
+}
+
+/// Unauthenticated part of the bridge configuration.
+#[derive(Debug, Deserialize)]
+pub struct BridgeConfig {
+    pub name: String,
+    #[serde(rename = "bridgeid")]
+    pub bridge_id: String,
+    #[serde(rename = "modelid")]
+    pub model_id: String,
+    #[serde(rename = "apiversion")]
+    pub api_version: String,
+    #[serde(rename = "swversion")]
+    pub software_version: String,
+    #[serde(rename = "mac")]
+    pub mac_address: String,
+}
+
+/// Requests the configuration of the bridge, no registered user is needed for this.
+pub fn get_config(address: &Address, timeout: Duration) -> Result<BridgeConfig, Error> {
+    let timeout = timeout.as_millis() as u64;
+    let response = ureq::get(&format!("{}/api/config", address.base_url()))
+        .timeout_connect(timeout)
+        .timeout_read(timeout)
+        .call();
+    if let Some(e) = response.synthetic_error() {
+        return Err(Error::Request(e.to_string()));
+    }
+    response.into_json_deserialize().map_err(Error::Io)
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Nupnp(huelib::Error),
+    Io(io::Error),
+    Request(String),
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Self::Nupnp(e) => write!(f, "{}", e),
+            Self::Io(e) => write!(f, "{}", e),
+            Self::Request(e) => write!(f, "{}", e),
+        }
+    }
+}
@@ -0,0 +1,402 @@
+use crate::{address, api, cache, config, discover, resolve, select};
+use serde::Serialize;
+use std::{fmt, sync::OnceLock};
+
+static FORMAT: OnceLock<Format> = OnceLock::new();
+
+/// Format in which errors are printed to stderr.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+/// Sets the error format, must be called before any error is printed.
+pub fn init(format: Format) {
+    let _ = FORMAT.set(format);
+}
+
+/// Category of an error, every category exits with its own code.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    /// Any error that does not belong to another category, exits with code 1.
+    General,
+    /// The bridge could not be reached or its response could not be read, exits with code 2.
+    BridgeUnreachable,
+    /// The user is not registered on the bridge, exits with code 3.
+    Unauthorized,
+    /// The requested resource does not exist, exits with code 4.
+    NotFound,
+    /// A parameter or value was rejected, exits with code 5.
+    InvalidParameter,
+    /// The link button of the bridge was not pressed, exits with code 6.
+    LinkButtonNotPressed,
+    /// The bridge failed to process the request, exits with code 7.
+    BridgeInternal,
+}
+
+impl Kind {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Self::General => 1,
+            Self::BridgeUnreachable => 2,
+            Self::Unauthorized => 3,
+            Self::NotFound => 4,
+            Self::InvalidParameter => 5,
+            Self::LinkButtonNotPressed => 6,
+            Self::BridgeInternal => 7,
+        }
+    }
+
+    /// Returns the category of an error type of the Hue API.
+    pub fn from_api_error(error_type: u16) -> Self {
+        match error_type {
+            1 => Self::Unauthorized,
+            3 => Self::NotFound,
+            2 | 4..=8 | 11 | 201 => Self::InvalidParameter,
+            101 => Self::LinkButtonNotPressed,
+            901 | 950 => Self::BridgeInternal,
+            _ => Self::General,
+        }
+    }
+}
+
+/// Error that is printed before huectl exits.
+#[derive(Debug, Serialize)]
+pub struct Error {
+    kind: Kind,
+    code: i32,
+    message: String,
+    cause: Option<String>,
+    hint: Option<String>,
+}
+
+impl Error {
+    pub fn new<D: fmt::Display>(kind: Kind, description: D) -> Self {
+        Self {
+            kind,
+            code: kind.exit_code(),
+            message: description.to_string(),
+            cause: None,
+            hint: None,
+        }
+    }
+
+    /// Creates an error with the category of its cause.
+    ///
+    /// Errors of the Hue API are replaced with an explanation if one is known.
+    pub fn with_cause<D: fmt::Display, E: Categorize>(description: D, cause: &E) -> Self {
+        let (cause_description, hint) = match cause.explanation() {
+            Some(v) => (v.description, v.hint),
+            None => (cause.to_string(), None),
+        };
+        Self {
+            cause: Some(cause_description),
+            hint,
+            ..Self::new(cause.kind(), description)
+        }
+    }
+
+    /// Replaces the hint of the error.
+    pub fn with_hint<D: fmt::Display>(self, hint: D) -> Self {
+        Self {
+            hint: Some(hint.to_string()),
+            ..self
+        }
+    }
+
+    /// Prints the error in the selected error format and exits with the code of its category.
+    ///
+    /// If the bridge was unreachable or rejected the user, the bridge of the profile is verified
+    /// first.
+    pub fn exit(self) -> ! {
+        let error = match self.kind {
+            Kind::BridgeUnreachable | Kind::Unauthorized => crate::util::verify_bridge(self),
+            _ => self,
+        };
+        error.print();
+        std::process::exit(error.code)
+    }
+
+    fn print(&self) {
+        match FORMAT.get_or_init(|| Format::Text) {
+            Format::Text => match &self.hint {
+                Some(v) => eprintln!("{}\nHint: {}", self, v),
+                None => eprintln!("{}", self),
+            },
+            Format::Json => match serde_json::to_string(self) {
+                Ok(v) => eprintln!("{{\"error\":{}}}", v),
+                Err(_) => eprintln!("{}", self),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match &self.cause {
+            Some(v) => write!(f, "{}: {}", self.message, v),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Error whose category determines the exit code of huectl.
+pub trait Categorize: fmt::Display {
+    fn kind(&self) -> Kind {
+        Kind::General
+    }
+
+    /// Returns an explanation of the error if it was returned by the Hue API.
+    fn explanation(&self) -> Option<Explanation> {
+        None
+    }
+}
+
+impl Categorize for api::Error {
+    fn kind(&self) -> Kind {
+        match self {
+            Self::Request(_) => Kind::BridgeUnreachable,
+            Self::Response(e) => Kind::from_api_error(e.kind),
+            Self::Json(_) | Self::MissingId => Kind::General,
+        }
+    }
+
+    fn explanation(&self) -> Option<Explanation> {
+        match self {
+            Self::Response(e) => Explanation::new(e.kind, &e.address),
+            _ => None,
+        }
+    }
+}
+
+impl Categorize for huelib::Error {
+    fn kind(&self) -> Kind {
+        match self {
+            Self::Response(e) => Kind::from_api_error(e.kind as u16),
+            // The response of the bridge could not be received.
+            Self::ParseHttpResponse(_) => Kind::BridgeUnreachable,
+            _ => Kind::General,
+        }
+    }
+
+    fn explanation(&self) -> Option<Explanation> {
+        match self {
+            Self::Response(e) => Explanation::new(e.kind as u16, &e.address),
+            _ => None,
+        }
+    }
+}
+
+impl Categorize for discover::Error {
+    fn kind(&self) -> Kind {
+        match self {
+            Self::Nupnp(e) => e.kind(),
+            Self::Io(_) | Self::Request(_) => Kind::BridgeUnreachable,
+        }
+    }
+
+    fn explanation(&self) -> Option<Explanation> {
+        match self {
+            Self::Nupnp(e) => e.explanation(),
+            _ => None,
+        }
+    }
+}
+
+impl Categorize for address::Error {
+    fn kind(&self) -> Kind {
+        Kind::InvalidParameter
+    }
+}
+
+impl Categorize for resolve::Error {
+    fn kind(&self) -> Kind {
+        match self {
+            Self::Bridge(e) => e.kind(),
+            Self::NotFound(..) => Kind::NotFound,
+            Self::Ambiguous(..) => Kind::InvalidParameter,
+        }
+    }
+
+    fn explanation(&self) -> Option<Explanation> {
+        match self {
+            Self::Bridge(e) => e.explanation(),
+            _ => None,
+        }
+    }
+}
+
+impl Categorize for select::Error {
+    fn kind(&self) -> Kind {
+        match self {
+            Self::Parse(_) | Self::Unsupported(_) => Kind::InvalidParameter,
+            Self::Resolve(e) => e.kind(),
+            Self::Bridge(e) => e.kind(),
+        }
+    }
+
+    fn explanation(&self) -> Option<Explanation> {
+        match self {
+            Self::Resolve(e) => e.explanation(),
+            Self::Bridge(e) => e.explanation(),
+            _ => None,
+        }
+    }
+}
+
+impl Categorize for cache::Error {}
+impl Categorize for config::Error {}
+impl Categorize for serde_json::Error {}
+impl Categorize for serde_yaml::Error {}
+
+/// Description of an error of the Hue API with a hint how to resolve it.
+#[derive(Debug)]
+pub struct Explanation {
+    pub description: String,
+    pub hint: Option<String>,
+}
+
+impl Explanation {
+    /// Explains an error type of the Hue API, the address is the path of the resource or attribute
+    /// that caused the error, e.g. `/lights/5/state/hue`.
+    pub fn new(error_type: u16, address: &str) -> Option<Self> {
+        let segments: Vec<&str> = address.split('/').filter(|v| !v.is_empty()).collect();
+        let kind = segments.first().map(|v| v.trim_end_matches('s'));
+        let resource = match (kind, segments.get(1)) {
+            (Some(kind), Some(id)) => format!("{} {}", kind, id),
+            (Some(kind), None) => kind.to_owned(),
+            _ => "resource".to_owned(),
+        };
+        let attribute = segments.last().copied().unwrap_or_default();
+        let list_hint = || kind.map(|v| format!("List the available ids with `huectl {} get`", v));
+        let (description, hint) = match error_type {
+            1 => (
+                "The user is not registered on the bridge".to_owned(),
+                Some(
+                    "Register a user with `huectl register --save <profile>` or check the \
+                     username of the profile"
+                        .to_owned(),
+                ),
+            ),
+            2 => ("The request body contains invalid JSON".to_owned(), None),
+            3 => (format!("The {} does not exist", resource), list_hint()),
+            4 => (
+                format!("The {} does not support this request", resource),
+                None,
+            ),
+            5 => ("The request is missing parameters".to_owned(), None),
+            6 => (
+                format!("The {} does not support '{}'", resource, attribute),
+                None,
+            ),
+            7 => (
+                format!(
+                    "The value of '{}' is invalid for the {}",
+                    attribute, resource
+                ),
+                kind.map(|v| format!("Check the allowed values with `huectl {} set --help`", v)),
+            ),
+            8 => (
+                format!("'{}' of the {} can not be modified", attribute, resource),
+                None,
+            ),
+            11 => ("The list contains too many items".to_owned(), None),
+            12 => (
+                "The request requires a connection to the Hue portal".to_owned(),
+                Some("Connect the bridge to the internet".to_owned()),
+            ),
+            101 => (
+                "The link button of the bridge was not pressed".to_owned(),
+                Some(
+                    "Press the link button and run the command again, or use `huectl register \
+                     --wait 30s`"
+                        .to_owned(),
+                ),
+            ),
+            201 => {
+                let attribute = match attribute {
+                    "hue" | "sat" | "xy" | "ct" | "effect" => "color",
+                    "bri" => "brightness",
+                    v => v,
+                };
+                (
+                    format!("The {} is off", resource),
+                    Some(format!("Add --on to change the {}", attribute)),
+                )
+            }
+            203 => (
+                "The light search failed, the list of new lights of the bridge is full".to_owned(),
+                None,
+            ),
+            301 => (
+                "The group could not be created, the group table of the bridge is full".to_owned(),
+                Some("Delete unused groups with `huectl group delete`".to_owned()),
+            ),
+            302 => (
+                "The device could not be added, the device set of the group is full".to_owned(),
+                None,
+            ),
+            305 => (
+                format!("The {} can not be modified or deleted", resource),
+                None,
+            ),
+            306 => (
+                "A light is already used in another room".to_owned(),
+                Some("Remove the light from its current room first".to_owned()),
+            ),
+            402 => (
+                "The scene could not be created, the scene buffer of the bridge is full".to_owned(),
+                Some("Delete unused scenes with `huectl scene delete`".to_owned()),
+            ),
+            403 => (format!("The {} could not be deleted", resource), None),
+            404 => (
+                "The scene could not be created because the group has no lights".to_owned(),
+                Some("Add lights to the group with `huectl group set --lights`".to_owned()),
+            ),
+            501 => (
+                "The bridge does not allow to create sensors of this type".to_owned(),
+                None,
+            ),
+            502 => (
+                "The sensor could not be created, the sensor list of the bridge is full".to_owned(),
+                Some("Delete unused sensors with `huectl sensor delete`".to_owned()),
+            ),
+            503 => (
+                "The sensor search failed, the list of new sensors of the bridge is full"
+                    .to_owned(),
+                None,
+            ),
+            601 => (
+                "The rule could not be created, the rule engine of the bridge is full".to_owned(),
+                Some("Delete unused rules with `huectl rule delete`".to_owned()),
+            ),
+            607 => ("A condition of the rule is invalid".to_owned(), None),
+            608 => ("An action of the rule is invalid".to_owned(), None),
+            609 => ("The rule could not be activated".to_owned(), None),
+            701 => (
+                "The schedule could not be created, the schedule list of the bridge is full"
+                    .to_owned(),
+                Some("Delete unused schedules with `huectl schedule delete`".to_owned()),
+            ),
+            702 => ("The time zone of the schedule is invalid".to_owned(), None),
+            703 => (
+                "The time and local time of a schedule can not be set both".to_owned(),
+                None,
+            ),
+            704 => ("The schedule could not be created".to_owned(), None),
+            706 => ("The command of the schedule is invalid".to_owned(), None),
+            705 => (
+                "The schedule can not be enabled because its time is in the past".to_owned(),
+                None,
+            ),
+            901 | 950 => (
+                "An internal error occurred on the bridge".to_owned(),
+                Some("Try again, if the error persists restart the bridge".to_owned()),
+            ),
+            _ => return None,
+        };
+        Some(Self { description, hint })
+    }
+}
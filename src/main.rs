@@ -4,9 +4,16 @@ extern crate envconfig_derive;
 #[macro_use]
 mod util;
 
+mod address;
+mod api;
 mod arg;
+mod cache;
 mod config;
+mod discover;
+mod error;
 mod output;
+mod resolve;
+mod select;
 
 fn main() {
     arg::exec();
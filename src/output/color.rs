@@ -0,0 +1,131 @@
+/// Named colors that are used to describe the color of a light.
+const COLOR_NAMES: &[(&str, (u8, u8, u8))] = &[
+    ("red", (255, 0, 0)),
+    ("orange", (255, 128, 0)),
+    ("amber", (255, 191, 0)),
+    ("yellow", (255, 255, 0)),
+    ("lime", (128, 255, 0)),
+    ("green", (0, 255, 0)),
+    ("spring green", (0, 255, 128)),
+    ("cyan", (0, 255, 255)),
+    ("azure", (0, 128, 255)),
+    ("blue", (0, 0, 255)),
+    ("violet", (128, 0, 255)),
+    ("magenta", (255, 0, 255)),
+    ("pink", (255, 0, 128)),
+    ("candlelight", (255, 147, 41)),
+    ("warm white", (255, 180, 107)),
+    ("soft white", (255, 209, 163)),
+    ("neutral white", (255, 228, 206)),
+    ("daylight", (255, 249, 253)),
+    ("cool white", (235, 238, 255)),
+];
+
+/// Converts a brightness from 0 to 254 into a percentage.
+pub fn brightness_percentage(brightness: u8) -> f32 {
+    round(brightness as f32 / 254.0 * 100.0)
+}
+
+/// Converts a saturation from 0 to 254 into a percentage.
+pub fn saturation_percentage(saturation: u8) -> f32 {
+    round(saturation as f32 / 254.0 * 100.0)
+}
+
+/// Converts a hue from 0 to 65535 into degrees.
+pub fn hue_degrees(hue: u16) -> f32 {
+    round(hue as f32 / 65535.0 * 360.0)
+}
+
+/// Converts a color temperature from mireds into Kelvin.
+pub fn kelvin(mired: u16) -> Option<u32> {
+    match mired {
+        0 => None,
+        v => Some((1_000_000.0 / v as f32).round() as u32),
+    }
+}
+
+/// Returns the approximate sRGB color of a hue and saturation at full brightness.
+pub fn rgb_from_hue_saturation(hue: u16, saturation: u8) -> (u8, u8, u8) {
+    let hue = hue as f32 / 65535.0 * 6.0;
+    let saturation = saturation as f32 / 254.0;
+    let channel = |offset: f32| {
+        let k = (offset + hue) % 6.0;
+        1.0 - saturation * k.min(4.0 - k).clamp(0.0, 1.0)
+    };
+    to_bytes(channel(5.0), channel(3.0), channel(1.0))
+}
+
+/// Returns the approximate sRGB color of CIE xy coordinates at full brightness.
+pub fn rgb_from_xy(x: f32, y: f32) -> (u8, u8, u8) {
+    if y <= 0.0 {
+        return (0, 0, 0);
+    }
+    let (big_x, big_y, big_z) = (x / y, 1.0, (1.0 - x - y) / y);
+    let r = big_x * 3.2406 - big_y * 1.5372 - big_z * 0.4986;
+    let g = -big_x * 0.9689 + big_y * 1.8758 + big_z * 0.0415;
+    let b = big_x * 0.0557 - big_y * 0.2040 + big_z * 1.0570;
+    let (r, g, b) = (r.max(0.0), g.max(0.0), b.max(0.0));
+    // Scale the color so that the brightest channel is at its maximum.
+    let max = r.max(g).max(b);
+    if max <= 0.0 {
+        return (0, 0, 0);
+    }
+    let gamma = |v: f32| {
+        if v <= 0.003_130_8 {
+            12.92 * v
+        } else {
+            1.055 * v.powf(1.0 / 2.4) - 0.055
+        }
+    };
+    to_bytes(gamma(r / max), gamma(g / max), gamma(b / max))
+}
+
+/// Returns the approximate sRGB color of a black body with the given temperature in Kelvin.
+pub fn rgb_from_kelvin(kelvin: u32) -> (u8, u8, u8) {
+    let t = kelvin as f32 / 100.0;
+    let r = if t <= 66.0 {
+        255.0
+    } else {
+        329.699 * (t - 60.0).powf(-0.133_204_76)
+    };
+    let g = if t <= 66.0 {
+        99.470_8 * t.ln() - 161.119_57
+    } else {
+        288.122_17 * (t - 60.0).powf(-0.075_514_85)
+    };
+    let b = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.517_73 * (t - 10.0).ln() - 305.044_8
+    };
+    to_bytes(r / 255.0, g / 255.0, b / 255.0)
+}
+
+/// Formats a color as hexadecimal sRGB value, e.g. `#ff8000`.
+pub fn hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Returns the name of the named color that is closest to the given color.
+pub fn name((r, g, b): (u8, u8, u8)) -> &'static str {
+    let distance = |(r2, g2, b2): (u8, u8, u8)| {
+        let square = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        square(r, r2) + square(g, g2) + square(b, b2)
+    };
+    COLOR_NAMES
+        .iter()
+        .min_by_key(|(_, color)| distance(*color))
+        .map(|(name, _)| *name)
+        .unwrap_or_default()
+}
+
+fn to_bytes(r: f32, g: f32, b: f32) -> (u8, u8, u8) {
+    let byte = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    (byte(r), byte(g), byte(b))
+}
+
+fn round(v: f32) -> f32 {
+    (v * 10.0).round() / 10.0
+}
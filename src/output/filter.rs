@@ -0,0 +1,127 @@
+use super::table;
+use serde_json::Value as JsonValue;
+use std::cmp::Ordering;
+
+/// Predicate on a field of a resource.
+///
+/// The syntax is `<field><operator><value>`, where the operator is `=` (equal), `!=` (not equal),
+/// `~` (contains) or `!~` (does not contain). Values are compared case-insensitively.
+#[derive(Clone, Debug)]
+pub struct Filter {
+    field: String,
+    operator: Operator,
+    value: String,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Operator {
+    Equal,
+    NotEqual,
+    Contains,
+    NotContains,
+}
+
+impl Filter {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let operators = [
+            ("!=", Operator::NotEqual),
+            ("!~", Operator::NotContains),
+            ("=", Operator::Equal),
+            ("~", Operator::Contains),
+        ];
+        let (index, operator, length) = operators
+            .iter()
+            .filter_map(|(token, operator)| s.find(token).map(|v| (v, *operator, token.len())))
+            .min_by_key(|(index, _, length)| (*index, usize::MAX - length))
+            .ok_or_else(|| format!("Missing operator in filter '{}'", s))?;
+        let field = s[..index].trim();
+        if field.is_empty() {
+            return Err(format!("Missing field in filter '{}'", s));
+        }
+        Ok(Self {
+            field: field.to_owned(),
+            operator,
+            value: s[index + length..].trim().to_lowercase(),
+        })
+    }
+
+    pub fn matches(&self, resource: &JsonValue) -> bool {
+        let value = table::cell(&field(resource, &self.field)).to_lowercase();
+        match self.operator {
+            Operator::Equal => value == self.value,
+            Operator::NotEqual => value != self.value,
+            Operator::Contains => value.contains(&self.value),
+            Operator::NotContains => !value.contains(&self.value),
+        }
+    }
+}
+
+/// Key by which a list of resources is sorted, a leading `-` sorts in descending order.
+#[derive(Clone, Debug)]
+pub struct Sort {
+    field: String,
+    descending: bool,
+}
+
+impl Sort {
+    pub fn parse(s: &str) -> Self {
+        match s.strip_prefix('-') {
+            Some(v) => Self {
+                field: v.to_owned(),
+                descending: true,
+            },
+            None => Self {
+                field: s.to_owned(),
+                descending: false,
+            },
+        }
+    }
+
+    pub fn apply(&self, resources: &mut [JsonValue]) {
+        resources.sort_by(|a, b| {
+            let ordering = compare(&field(a, &self.field), &field(b, &self.field));
+            if self.descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+}
+
+/// Returns the value of a field with a path separated by dots.
+///
+/// If the path does not exist, the first nested field that ends with the path is used, so that
+/// e.g. `reachable` matches `state.reachable`.
+fn field(resource: &JsonValue, path: &str) -> JsonValue {
+    if let Some(v) = resource.pointer(&format!("/{}", path.replace('.', "/"))) {
+        return v.clone();
+    }
+    let suffix = format!(".{}", path);
+    table::flatten(resource)
+        .into_iter()
+        .find(|(key, _)| key.ends_with(&suffix))
+        .map(|(_, value)| value)
+        .unwrap_or(JsonValue::Null)
+}
+
+/// Compares two values, numeric strings like identifiers are compared as numbers and null values
+/// are sorted last.
+fn compare(a: &JsonValue, b: &JsonValue) -> Ordering {
+    let number = |v: &JsonValue| match v {
+        JsonValue::Number(v) => v.as_f64(),
+        JsonValue::String(v) => v.parse::<f64>().ok(),
+        _ => None,
+    };
+    match (a, b) {
+        (JsonValue::Null, JsonValue::Null) => Ordering::Equal,
+        (JsonValue::Null, _) => Ordering::Greater,
+        (_, JsonValue::Null) => Ordering::Less,
+        _ => match (number(a), number(b)) {
+            (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+            _ => table::cell(a)
+                .to_lowercase()
+                .cmp(&table::cell(b).to_lowercase()),
+        },
+    }
+}
@@ -0,0 +1,109 @@
+use super::{
+    filter::{Filter, Sort},
+    query::{self, Query},
+    table::{self, Render},
+};
+use serde_json::Value as JsonValue;
+use std::sync::OnceLock;
+
+static FORMAT: OnceLock<Format> = OnceLock::new();
+
+/// Format in which the output is printed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    Json,
+    JsonCompact,
+    Ndjson,
+    Yaml,
+    Table,
+    Plain,
+}
+
+/// Sets the output format, must be called before anything is printed.
+pub fn init(format: Format) {
+    let _ = FORMAT.set(format);
+}
+
+fn format() -> Format {
+    *FORMAT.get_or_init(|| Format::Json)
+}
+
+/// Options of the output that are set by `get` subcommands.
+#[derive(Debug, Default)]
+pub struct Options {
+    /// Predicates that the items of a list must match.
+    pub filters: Vec<Filter>,
+    /// Key by which the items of a list are sorted.
+    pub sort: Option<Sort>,
+    /// Columns of the table output.
+    pub columns: Option<Vec<String>>,
+    /// Paths of the fields that are kept.
+    pub fields: Option<Vec<String>>,
+    /// Query that is evaluated on the output.
+    pub query: Option<Query>,
+}
+
+/// Prints a value in the selected output format.
+pub fn print<T: Render>(value: &T) {
+    print_with(value, &Options::default());
+}
+
+/// Prints a value in the selected output format with the given options.
+pub fn print_with<T: Render>(value: &T, options: &Options) {
+    let mut value = match serde_json::to_value(value) {
+        Ok(v) => v,
+        Err(e) => exit!("Failed to serialize output", e),
+    };
+    if let JsonValue::Array(items) = &mut value {
+        items.retain(|v| options.filters.iter().all(|filter| filter.matches(v)));
+        if let Some(v) = &options.sort {
+            v.apply(items);
+        }
+    }
+    // The default columns only apply to unmodified resources.
+    let mut columns = T::columns();
+    if let Some(v) = &options.fields {
+        value = query::select_fields(&value, v);
+        columns = Vec::new();
+    }
+    if let Some(v) = &options.query {
+        value = v.evaluate(&value);
+        columns = Vec::new();
+    }
+    let output = match (format(), &options.columns) {
+        (Format::Table, Some(v)) => table::render(&value, table::select(columns, v), true),
+        (Format::Table, None) => table::render(&value, columns, false),
+        (v, _) => render(&value, v),
+    };
+    print!("{}", output);
+}
+
+fn render(value: &JsonValue, format: Format) -> String {
+    match format {
+        Format::Json => format!("{}\n", serde_json::to_string_pretty(value).unwrap()),
+        Format::JsonCompact => format!("{}\n", value),
+        Format::Ndjson => match value {
+            JsonValue::Array(v) => v.iter().map(|v| format!("{}\n", v)).collect(),
+            _ => format!("{}\n", value),
+        },
+        Format::Yaml => match serde_yaml::to_string(value) {
+            Ok(v) => format!("{}\n", v.trim_start_matches("---\n").trim_end()),
+            Err(e) => exit!("Failed to serialize output", e),
+        },
+        Format::Table => table::render(value, Vec::new(), false),
+        Format::Plain => match value {
+            // Objects are separated by an empty line, other values are printed line by line.
+            JsonValue::Array(v) if v.iter().any(JsonValue::is_object) => v
+                .iter()
+                .map(|v| render(v, Format::Plain))
+                .collect::<Vec<String>>()
+                .join("\n"),
+            JsonValue::Array(v) => v.iter().map(|v| format!("{}\n", table::cell(v))).collect(),
+            JsonValue::Object(_) => table::flatten(value)
+                .into_iter()
+                .map(|(key, value)| format!("{}: {}\n", key, table::cell(&value)))
+                .collect(),
+            _ => format!("{}\n", table::cell(value)),
+        },
+    }
+}
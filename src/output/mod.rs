@@ -0,0 +1,1008 @@
+mod color;
+mod filter;
+mod format;
+mod query;
+mod table;
+
+pub use filter::{Filter, Sort};
+pub use format::{init, print, print_with, Format, Options};
+pub use query::Query;
+
+use huelib::{resource, response};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::{collections::HashMap, net::IpAddr};
+
+#[derive(Serialize)]
+pub struct Bridge {
+    ip_address: String,
+    name: Option<String>,
+    bridge_id: Option<String>,
+    model_id: Option<String>,
+    api_version: Option<String>,
+    software_version: Option<String>,
+    mac_address: Option<String>,
+}
+
+impl Bridge {
+    pub fn new(ip_address: IpAddr, config: Option<crate::discover::BridgeConfig>) -> Self {
+        match config {
+            Some(v) => Self {
+                ip_address: ip_address.to_string(),
+                name: Some(v.name),
+                bridge_id: Some(v.bridge_id),
+                model_id: Some(v.model_id),
+                api_version: Some(v.api_version),
+                software_version: Some(v.software_version),
+                mac_address: Some(v.mac_address),
+            },
+            None => Self {
+                ip_address: ip_address.to_string(),
+                name: None,
+                bridge_id: None,
+                model_id: None,
+                api_version: None,
+                software_version: None,
+                mac_address: None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Config {
+    name: String,
+    software_update: ConfigSoftwareUpdate,
+    software_version: String,
+    api_version: String,
+    link_button: bool,
+    ip_address: String,
+    mac_address: String,
+    netmask: String,
+    gateway: String,
+    dhcp: bool,
+    portal_services: bool,
+    portal_connection: String,
+    portal_state: ConfigPortalState,
+    internet_services: ConfigInternetServices,
+    current_time: String,
+    local_time: Option<String>,
+    timezone: Option<String>,
+    zigbee_channel: u8,
+    model_id: String,
+    bridge_id: String,
+    factory_new: bool,
+    replaces_bridge_id: Option<String>,
+    datastore_version: String,
+    starterkit_id: String,
+    backup: ConfigBackup,
+    whitelist: Vec<User>,
+}
+
+impl From<resource::Config> for Config {
+    fn from(v: resource::Config) -> Self {
+        Self {
+            name: v.name,
+            software_update: ConfigSoftwareUpdate::from(v.software_update),
+            software_version: v.software_version,
+            api_version: v.api_version,
+            link_button: v.link_button,
+            ip_address: v.ip_address.to_string(),
+            mac_address: v.mac_address,
+            netmask: v.netmask,
+            gateway: v.gateway.to_string(),
+            dhcp: v.dhcp,
+            portal_services: v.portal_services,
+            portal_connection: service_status(v.portal_connection),
+            portal_state: ConfigPortalState::from(v.portal_state),
+            internet_services: ConfigInternetServices::from(v.internet_services),
+            current_time: v.current_time.to_string(),
+            local_time: v.local_time.map(|v| v.to_string()),
+            timezone: v.timezone,
+            zigbee_channel: v.zigbee_channel,
+            model_id: v.model_id,
+            bridge_id: v.bridge_id,
+            factory_new: v.factory_new,
+            replaces_bridge_id: v.replaces_bridge_id,
+            datastore_version: v.datastore_version,
+            starterkit_id: v.starterkit_id,
+            backup: ConfigBackup::from(v.backup),
+            whitelist: v.whitelist.into_iter().map(User::from).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ConfigSoftwareUpdate {
+    state: String,
+    check: bool,
+    last_change: Option<String>,
+    last_install: Option<String>,
+    auto_install_on: bool,
+    auto_install_time: Option<String>,
+}
+
+impl From<resource::config::SoftwareUpdate> for ConfigSoftwareUpdate {
+    fn from(v: resource::config::SoftwareUpdate) -> Self {
+        use resource::config::SoftwareUpdateState;
+        Self {
+            state: match v.state {
+                SoftwareUpdateState::NoUpdates => "NoUpdates",
+                SoftwareUpdateState::Transferring => "Transferring",
+                SoftwareUpdateState::AnyReadyToInstall => "AnyReadyToInstall",
+                SoftwareUpdateState::AllReadyToInstall => "AllReadyToInstall",
+                SoftwareUpdateState::Installing => "Installing",
+                SoftwareUpdateState::Unkown => "Unknown",
+            }
+            .to_owned(),
+            check: v.check,
+            last_change: v.last_change.map(|v| v.to_string()),
+            last_install: v.last_install.map(|v| v.to_string()),
+            auto_install_on: v.auto_install.on,
+            auto_install_time: v.auto_install.update_time.map(|v| v.to_string()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ConfigPortalState {
+    signed_on: bool,
+    incoming: bool,
+    outgoing: bool,
+    communication: String,
+}
+
+impl From<resource::config::PortalState> for ConfigPortalState {
+    fn from(v: resource::config::PortalState) -> Self {
+        Self {
+            signed_on: v.signedon,
+            incoming: v.incoming,
+            outgoing: v.outgoing,
+            communication: service_status(v.communication),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ConfigInternetServices {
+    internet: String,
+    remote_access: String,
+    time: String,
+    software_update: String,
+}
+
+impl From<resource::config::InternetServices> for ConfigInternetServices {
+    fn from(v: resource::config::InternetServices) -> Self {
+        Self {
+            internet: service_status(v.internet),
+            remote_access: service_status(v.remote_access),
+            time: service_status(v.time),
+            software_update: service_status(v.software_update),
+        }
+    }
+}
+
+fn service_status(v: resource::config::ServiceStatus) -> String {
+    use resource::config::ServiceStatus;
+    match v {
+        ServiceStatus::Connected => "Connected",
+        ServiceStatus::Disconnected => "Disconnected",
+    }
+    .to_owned()
+}
+
+#[derive(Serialize)]
+struct ConfigBackup {
+    status: String,
+    error: Option<String>,
+}
+
+impl From<resource::config::Backup> for ConfigBackup {
+    fn from(v: resource::config::Backup) -> Self {
+        use resource::config::{BackupError, BackupStatus};
+        Self {
+            status: match v.status {
+                BackupStatus::Idle => "Idle",
+                BackupStatus::StartMigration => "StartMigration",
+                BackupStatus::FilereadyDisabled => "FilereadyDisabled",
+                BackupStatus::PrepareRestore => "PrepareRestore",
+                BackupStatus::Restoring => "Restoring",
+            }
+            .to_owned(),
+            error: match v.error {
+                BackupError::ExportFailed => Some("ExportFailed".to_owned()),
+                BackupError::ImportFailed => Some("ImportFailed".to_owned()),
+                BackupError::None => None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct User {
+    pub id: String,
+    pub name: String,
+    last_used: String,
+    created: String,
+}
+
+impl From<resource::config::User> for User {
+    fn from(v: resource::config::User) -> Self {
+        Self {
+            id: v.id,
+            name: v.name,
+            last_used: v.last_use_date.to_string(),
+            created: v.create_date.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Group {
+    id: String,
+    name: String,
+    lights: Vec<String>,
+    sensors: Vec<String>,
+    kind: String,
+    class: Option<String>,
+    state: Option<GroupState>,
+    model_id: Option<String>,
+    unique_id: Option<String>,
+    recycle: Option<bool>,
+}
+
+impl From<resource::Group> for Group {
+    fn from(v: resource::Group) -> Self {
+        use resource::group::Kind;
+        Self {
+            id: v.id,
+            name: v.name,
+            lights: v.lights,
+            sensors: v.sensors,
+            kind: match v.kind {
+                Kind::Creatable(v) => format!("{:?}", v),
+                Kind::Immutable(v) => format!("{:?}", v),
+            },
+            class: v.class.map(|v| format!("{:?}", v)),
+            state: v.state.map(GroupState::from),
+            model_id: v.model_id,
+            unique_id: v.unique_id,
+            recycle: v.recycle,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct GroupState {
+    any_on: bool,
+    all_on: bool,
+}
+
+impl From<resource::group::State> for GroupState {
+    fn from(v: resource::group::State) -> Self {
+        Self {
+            any_on: v.any_on,
+            all_on: v.all_on,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Light {
+    id: String,
+    name: String,
+    kind: String,
+    state: LightState,
+    model_id: String,
+    unique_id: String,
+    product_id: Option<String>,
+    product_name: Option<String>,
+    manufacturer_name: Option<String>,
+    software_version: String,
+    software_update: LightSoftwareUpdate,
+    config: LightConfig,
+    capabilities: LightCapabilities,
+}
+
+impl Light {
+    /// Removes the values that are not sent by the bridge but computed from the raw values.
+    pub fn into_raw(mut self) -> Self {
+        self.state.derived = None;
+        self
+    }
+}
+
+impl From<resource::Light> for Light {
+    fn from(v: resource::Light) -> Self {
+        Self {
+            id: v.id,
+            name: v.name,
+            kind: v.kind,
+            state: LightState::from(v.state),
+            model_id: v.model_id,
+            unique_id: v.unique_id,
+            product_id: v.product_id,
+            product_name: v.product_name,
+            manufacturer_name: v.manufacturer_name,
+            software_version: v.software_version,
+            software_update: LightSoftwareUpdate::from(v.software_update),
+            config: LightConfig::from(v.config),
+            capabilities: LightCapabilities::from(v.capabilities),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct LightState {
+    on: Option<bool>,
+    brightness: Option<u8>,
+    hue: Option<u16>,
+    saturation: Option<u8>,
+    color_space_coordinates: Option<(f32, f32)>,
+    color_temperature: Option<u16>,
+    alert: Option<Alert>,
+    effect: Option<Effect>,
+    color_mode: Option<ColorMode>,
+    reachable: bool,
+    #[serde(flatten)]
+    derived: Option<LightDerivedState>,
+}
+
+impl From<resource::light::State> for LightState {
+    fn from(v: resource::light::State) -> Self {
+        Self {
+            derived: Some(LightDerivedState::new(&v)),
+            on: v.on,
+            brightness: v.brightness,
+            hue: v.hue,
+            saturation: v.saturation,
+            color_space_coordinates: v.color_space_coordinates,
+            color_temperature: v.color_temperature,
+            alert: v.alert.map(Alert::from),
+            effect: v.effect.map(Effect::from),
+            color_mode: v.color_mode.map(ColorMode::from),
+            reachable: v.reachable,
+        }
+    }
+}
+
+/// Values of the light state in common units that are computed from the raw values.
+#[derive(Serialize)]
+struct LightDerivedState {
+    brightness_percentage: Option<f32>,
+    hue_degrees: Option<f32>,
+    saturation_percentage: Option<f32>,
+    color_temperature_kelvin: Option<u32>,
+    /// Approximate sRGB color at full brightness in the active color mode.
+    rgb: Option<String>,
+    /// Name of the named color that is closest to the sRGB color.
+    color_name: Option<String>,
+}
+
+impl LightDerivedState {
+    fn new(v: &resource::light::State) -> Self {
+        use resource::ColorMode;
+        let rgb = match v.color_mode {
+            Some(ColorMode::HueAndSaturation) => v
+                .hue
+                .zip(v.saturation)
+                .map(|(hue, saturation)| color::rgb_from_hue_saturation(hue, saturation)),
+            Some(ColorMode::ColorSpaceCoordinates) => v
+                .color_space_coordinates
+                .map(|(x, y)| color::rgb_from_xy(x, y)),
+            Some(ColorMode::ColorTemperature) => v
+                .color_temperature
+                .and_then(color::kelvin)
+                .map(color::rgb_from_kelvin),
+            None => None,
+        };
+        Self {
+            brightness_percentage: v.brightness.map(color::brightness_percentage),
+            hue_degrees: v.hue.map(color::hue_degrees),
+            saturation_percentage: v.saturation.map(color::saturation_percentage),
+            color_temperature_kelvin: v.color_temperature.and_then(color::kelvin),
+            rgb: rgb.map(color::hex),
+            color_name: rgb.map(|v| color::name(v).to_owned()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct LightSoftwareUpdate {
+    state: String,
+    last_install: Option<String>,
+}
+
+impl From<resource::light::SoftwareUpdate> for LightSoftwareUpdate {
+    fn from(v: resource::light::SoftwareUpdate) -> Self {
+        Self {
+            state: format!("{:?}", v.state),
+            last_install: v.last_install.map(|v| v.to_string()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct LightConfig {
+    archetype: String,
+    function: String,
+    direction: String,
+    startup: Option<LightStartup>,
+}
+
+impl From<resource::light::Config> for LightConfig {
+    fn from(v: resource::light::Config) -> Self {
+        Self {
+            archetype: v.arche_type,
+            function: v.function,
+            direction: v.direction,
+            startup: v.startup.map(LightStartup::from),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct LightStartup {
+    mode: String,
+    configured: bool,
+}
+
+impl From<resource::light::StartupConfig> for LightStartup {
+    fn from(v: resource::light::StartupConfig) -> Self {
+        Self {
+            mode: v.mode,
+            configured: v.configured,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct LightCapabilities {
+    certified: bool,
+    min_dim_level: Option<usize>,
+    max_lumen: Option<usize>,
+    color_gamut_type: Option<String>,
+    color_gamut: Option<LightColorGamut>,
+    color_temperature: Option<LightColorTemperatureRange>,
+    streaming_renderer: bool,
+    streaming_proxy: bool,
+}
+
+impl From<resource::light::Capabilities> for LightCapabilities {
+    fn from(v: resource::light::Capabilities) -> Self {
+        Self {
+            certified: v.certified,
+            min_dim_level: v.control.min_dimlevel,
+            max_lumen: v.control.max_lumen,
+            color_gamut_type: v.control.color_gamut_type,
+            color_gamut: v.control.color_gamut.and_then(LightColorGamut::new),
+            color_temperature: v
+                .control
+                .color_temperature
+                .map(LightColorTemperatureRange::from),
+            streaming_renderer: v.streaming.renderer,
+            streaming_proxy: v.streaming.proxy,
+        }
+    }
+}
+
+/// Corners of the triangle in the CIE color space that the light can display.
+#[derive(Serialize)]
+struct LightColorGamut {
+    red: (f32, f32),
+    green: (f32, f32),
+    blue: (f32, f32),
+}
+
+impl LightColorGamut {
+    fn new(v: Vec<(f32, f32)>) -> Option<Self> {
+        match v.as_slice() {
+            [red, green, blue] => Some(Self {
+                red: *red,
+                green: *green,
+                blue: *blue,
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct LightColorTemperatureRange {
+    min: usize,
+    max: usize,
+    min_kelvin: usize,
+    max_kelvin: usize,
+}
+
+impl From<resource::light::ColorTemperatureCapabilities> for LightColorTemperatureRange {
+    fn from(v: resource::light::ColorTemperatureCapabilities) -> Self {
+        // The range is in mireds, so the minimum corresponds to the maximum in kelvin.
+        let kelvin = |v: usize| 1_000_000usize.checked_div(v).unwrap_or(0);
+        Self {
+            min: v.min,
+            max: v.max,
+            min_kelvin: kelvin(v.max),
+            max_kelvin: kelvin(v.min),
+        }
+    }
+}
+
+/// Results of modifying the attributes of a resource.
+#[derive(Default, Serialize)]
+#[serde(transparent)]
+pub struct Modifications(Vec<Modification>);
+
+impl Modifications {
+    /// Adds the responses of the bridge to a modifier.
+    ///
+    /// The modifier is used to determine the requested value of attributes that failed to be
+    /// modified, as the bridge only responds with the address of those.
+    pub fn extend<T: Serialize>(
+        &mut self,
+        responses: Vec<crate::api::Response<response::Modified>>,
+        modifier: &T,
+    ) {
+        let requested = serde_json::to_value(modifier).unwrap_or_default();
+        self.0.extend(
+            responses
+                .into_iter()
+                .map(|v| Modification::new(v, &requested)),
+        );
+    }
+
+    /// Returns the Hue API error type of the first attribute that failed to be modified.
+    pub fn error_type(&self) -> Option<u16> {
+        self.0.iter().find_map(|v| v.error.as_ref().map(|v| v.kind))
+    }
+}
+
+#[derive(Serialize)]
+struct Modification {
+    path: String,
+    value: JsonValue,
+    success: bool,
+    error: Option<ModificationError>,
+}
+
+impl Modification {
+    fn new(response: crate::api::Response<response::Modified>, requested: &JsonValue) -> Self {
+        match response {
+            crate::api::Response::Success(v) => Self {
+                path: v.address,
+                value: v.value,
+                success: true,
+                error: None,
+            },
+            crate::api::Response::Error(v) => {
+                let attribute = v.address.rsplit('/').next().unwrap_or_default();
+                let explanation = crate::error::Explanation::new(v.kind, &v.address);
+                let (description, hint) = match explanation {
+                    Some(e) => (e.description, e.hint),
+                    None => (v.description, None),
+                };
+                Self {
+                    value: requested.get(attribute).cloned().unwrap_or_default(),
+                    path: v.address,
+                    success: false,
+                    error: Some(ModificationError {
+                        kind: v.kind,
+                        description,
+                        hint,
+                    }),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ModificationError {
+    kind: u16,
+    description: String,
+    hint: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct Resourcelink {
+    id: String,
+    name: String,
+    description: String,
+    owner: String,
+    kind: String,
+    class_id: u16,
+    recycle: bool,
+    links: Vec<ResourcelinkLink>,
+}
+
+impl From<resource::Resourcelink> for Resourcelink {
+    fn from(v: resource::Resourcelink) -> Self {
+        Self {
+            id: v.id,
+            name: v.name,
+            description: v.description,
+            owner: v.owner,
+            kind: format!("{:?}", v.kind),
+            class_id: v.class_id,
+            recycle: v.recycle,
+            links: v.links.into_iter().map(ResourcelinkLink::from).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ResourcelinkLink {
+    kind: String,
+    id: String,
+}
+
+impl From<resource::resourcelink::Link> for ResourcelinkLink {
+    fn from(v: resource::resourcelink::Link) -> Self {
+        Self {
+            kind: format!("{:?}", v.kind),
+            id: v.id,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Rule {
+    id: String,
+    name: String,
+    owner: Option<String>,
+    last_triggered: Option<String>,
+    times_triggered: usize,
+    created: String,
+    status: String,
+    conditions: Vec<RuleCondition>,
+    actions: Vec<Action>,
+}
+
+impl From<resource::Rule> for Rule {
+    fn from(v: resource::Rule) -> Self {
+        Self {
+            id: v.id,
+            name: v.name,
+            owner: v.owner,
+            last_triggered: v.last_triggered.map(|v| v.to_string()),
+            times_triggered: v.times_triggered,
+            created: v.created.to_string(),
+            status: format!("{:?}", v.status),
+            conditions: v.conditions.into_iter().map(RuleCondition::from).collect(),
+            actions: v.actions.into_iter().map(Action::from).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct RuleCondition {
+    address: String,
+    operator: String,
+    value: Option<String>,
+}
+
+impl From<resource::rule::Condition> for RuleCondition {
+    fn from(v: resource::rule::Condition) -> Self {
+        Self {
+            address: v.address,
+            operator: format!("{:?}", v.operator),
+            value: v.value,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Scene {
+    id: String,
+    name: String,
+    kind: String,
+    group: Option<String>,
+    lights: Option<Vec<String>>,
+    owner: Option<String>,
+    recycle: bool,
+    locked: bool,
+    app_data: SceneAppData,
+    picture: Option<String>,
+    last_update: Option<String>,
+    version: String,
+}
+
+impl From<resource::Scene> for Scene {
+    fn from(v: resource::Scene) -> Self {
+        Self {
+            id: v.id,
+            name: v.name,
+            kind: format!("{:?}", v.kind),
+            group: v.group,
+            lights: v.lights,
+            owner: v.owner,
+            recycle: v.recycle,
+            locked: v.locked,
+            app_data: SceneAppData::from(v.app_data),
+            picture: v.picture,
+            last_update: v.last_update.map(|v| v.to_string()),
+            version: format!("{:?}", v.version),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SceneAppData {
+    version: Option<i8>,
+    data: Option<String>,
+}
+
+impl From<resource::scene::AppData> for SceneAppData {
+    fn from(v: resource::scene::AppData) -> Self {
+        Self {
+            version: v.version,
+            data: v.data,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Schedule {
+    id: String,
+    name: String,
+    description: String,
+    action: Action,
+    local_time: String,
+    start_time: Option<String>,
+    status: String,
+    auto_delete: Option<bool>,
+}
+
+impl From<resource::Schedule> for Schedule {
+    fn from(v: resource::Schedule) -> Self {
+        Self {
+            id: v.id,
+            name: v.name,
+            description: v.description,
+            action: Action::from(v.action),
+            local_time: v.local_time,
+            start_time: v.start_time.map(|v| v.to_string()),
+            status: format!("{:?}", v.status),
+            auto_delete: v.auto_delete,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Sensor {
+    id: String,
+    name: String,
+    type_name: String,
+    model_id: String,
+    unique_id: Option<String>,
+    manufacturer_name: Option<String>,
+    software_version: String,
+    state: SensorState,
+    config: SensorConfig,
+    recycle: Option<bool>,
+}
+
+impl From<crate::api::Sensor> for Sensor {
+    fn from(v: crate::api::Sensor) -> Self {
+        Self {
+            id: v.id,
+            name: v.name,
+            type_name: v.type_name,
+            model_id: v.model_id,
+            unique_id: v.unique_id,
+            manufacturer_name: v.manufacturer_name,
+            software_version: v.software_version,
+            state: SensorState::from(v.state),
+            config: SensorConfig::from(v.config),
+            recycle: v.recycle,
+        }
+    }
+}
+
+/// State of a sensor, fields that are not supported by the type of the sensor are omitted.
+#[derive(Serialize)]
+pub struct SensorState {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    presence: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    flag: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    light_level: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lux: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dark: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    daylight: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    button_event: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    humidity: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    open: Option<bool>,
+    last_updated: Option<String>,
+}
+
+impl From<crate::api::SensorState> for SensorState {
+    fn from(v: crate::api::SensorState) -> Self {
+        Self {
+            presence: v.presence,
+            flag: v.flag,
+            // The bridge sends hundredths of a degree Celsius.
+            temperature: v.temperature.map(|v| v as f32 / 100.0),
+            light_level: v.light_level,
+            // The light level is `10000 * log10(lux) + 1`.
+            lux: v
+                .light_level
+                .map(|v| (10f32.powf((v as f32 - 1.0) / 10000.0) * 10.0).round() / 10.0),
+            dark: v.dark,
+            daylight: v.daylight,
+            button_event: v.button_event,
+            status: v.status,
+            // The bridge sends hundredths of a percent.
+            humidity: v.humidity.map(|v| v as f32 / 100.0),
+            open: v.open,
+            // The bridge sends "none" if the state was never updated.
+            last_updated: v.last_updated.filter(|v| v != "none"),
+        }
+    }
+}
+
+/// Config of a sensor, fields that are not supported by the type of the sensor are omitted.
+#[derive(Serialize)]
+pub struct SensorConfig {
+    on: bool,
+    reachable: Option<bool>,
+    battery: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    alert: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sensitivity: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sensitivity_max: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    led_indication: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user_test: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    threshold_dark: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    threshold_offset: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latitude: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    longitude: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sunrise_offset: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sunset_offset: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    configured: Option<bool>,
+}
+
+impl From<crate::api::SensorConfig> for SensorConfig {
+    fn from(v: crate::api::SensorConfig) -> Self {
+        Self {
+            on: v.on,
+            reachable: v.reachable,
+            battery: v.battery,
+            alert: v.alert,
+            sensitivity: v.sensitivity,
+            sensitivity_max: v.sensitivity_max,
+            led_indication: v.led_indication,
+            user_test: v.user_test,
+            threshold_dark: v.threshold_dark,
+            threshold_offset: v.threshold_offset,
+            latitude: v.latitude,
+            longitude: v.longitude,
+            sunrise_offset: v.sunrise_offset,
+            sunset_offset: v.sunset_offset,
+            configured: v.configured,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Scan {
+    last_scan: Option<String>,
+    resources: Vec<ScanResource>,
+}
+
+impl From<resource::Scan> for Scan {
+    fn from(v: resource::Scan) -> Self {
+        use resource::LastScan;
+        Self {
+            last_scan: match v.last_scan {
+                LastScan::DateTime(v) => Some(v.to_string()),
+                LastScan::Active => Some("Active".to_owned()),
+                LastScan::None => None,
+            },
+            resources: v.resources.into_iter().map(ScanResource::from).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ScanResource {
+    id: String,
+    name: String,
+}
+
+impl From<resource::ScanResource> for ScanResource {
+    fn from(v: resource::ScanResource) -> Self {
+        Self {
+            id: v.id,
+            name: v.name,
+        }
+    }
+}
+
+#[derive(Serialize)]
+enum Alert {
+    Select,
+    LSelect,
+    None,
+}
+
+impl From<resource::Alert> for Alert {
+    fn from(v: resource::Alert) -> Self {
+        match v {
+            resource::Alert::Select => Self::Select,
+            resource::Alert::LSelect => Self::LSelect,
+            resource::Alert::None => Self::None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+enum Effect {
+    Colorloop,
+    None,
+}
+
+impl From<resource::Effect> for Effect {
+    fn from(v: resource::Effect) -> Self {
+        match v {
+            resource::Effect::Colorloop => Self::Colorloop,
+            resource::Effect::None => Self::None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+enum ColorMode {
+    ColorSpaceCoordinates,
+    ColorTemperature,
+    HueAndSaturation,
+}
+
+impl From<resource::ColorMode> for ColorMode {
+    fn from(v: resource::ColorMode) -> Self {
+        match v {
+            resource::ColorMode::ColorSpaceCoordinates => Self::ColorSpaceCoordinates,
+            resource::ColorMode::ColorTemperature => Self::ColorTemperature,
+            resource::ColorMode::HueAndSaturation => Self::HueAndSaturation,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Action {
+    address: String,
+    request_type: String,
+    body: HashMap<String, JsonValue>,
+}
+
+impl From<resource::Action> for Action {
+    fn from(v: resource::Action) -> Self {
+        Self {
+            address: v.address,
+            request_type: format!("{:?}", v.request_type),
+            body: v.body,
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use serde_json::{Map, Value as JsonValue};
+
+/// Path expression that selects values of the output.
+///
+/// The syntax is a subset of JSONPath and jq, e.g. `$[*].state.on`, `.[].name` or
+/// `state.brightness`. An optional leading `$` or `.` is ignored.
+#[derive(Clone, Debug)]
+pub struct Query {
+    segments: Vec<Segment>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+    Wildcard,
+}
+
+impl Query {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut chars = s.trim().trim_start_matches('$').chars();
+        let mut segments = Vec::new();
+        let mut key = String::new();
+        while let Some(c) = chars.next() {
+            match c {
+                '.' => {
+                    if !key.is_empty() {
+                        segments.push(Segment::Key(std::mem::take(&mut key)));
+                    }
+                }
+                '[' => {
+                    if !key.is_empty() {
+                        segments.push(Segment::Key(std::mem::take(&mut key)));
+                    }
+                    let mut inner = String::new();
+                    loop {
+                        match chars.next() {
+                            Some(']') => break,
+                            Some(v) => inner.push(v),
+                            None => return Err(format!("Missing ']' in query '{}'", s)),
+                        }
+                    }
+                    let inner = inner.trim();
+                    let segment = match inner {
+                        "" | "*" => Segment::Wildcard,
+                        _ if inner.starts_with('"') || inner.starts_with('\'') => {
+                            Segment::Key(inner.trim_matches(|c| c == '"' || c == '\'').to_owned())
+                        }
+                        _ => match inner.parse() {
+                            Ok(v) => Segment::Index(v),
+                            Err(_) => return Err(format!("Invalid index '{}' in query", inner)),
+                        },
+                    };
+                    segments.push(segment);
+                }
+                '*' if key.is_empty() => segments.push(Segment::Wildcard),
+                _ => key.push(c),
+            }
+        }
+        if !key.is_empty() {
+            segments.push(Segment::Key(key));
+        }
+        Ok(Self { segments })
+    }
+
+    /// Evaluates the query.
+    ///
+    /// If the query contains a wildcard, a list of all matched values is returned, otherwise the
+    /// matched value or null.
+    pub fn evaluate(&self, value: &JsonValue) -> JsonValue {
+        let mut values = vec![value.clone()];
+        for segment in &self.segments {
+            values = values
+                .into_iter()
+                .flat_map(|v| match (segment, v) {
+                    (Segment::Key(key), JsonValue::Object(mut v)) => {
+                        v.remove(key).into_iter().collect()
+                    }
+                    (Segment::Index(index), JsonValue::Array(mut v)) if *index < v.len() => {
+                        vec![v.swap_remove(*index)]
+                    }
+                    (Segment::Wildcard, JsonValue::Array(v)) => v,
+                    (Segment::Wildcard, JsonValue::Object(v)) => {
+                        v.into_iter().map(|v| v.1).collect()
+                    }
+                    _ => Vec::new(),
+                })
+                .collect();
+        }
+        if self.segments.contains(&Segment::Wildcard) {
+            JsonValue::Array(values)
+        } else {
+            values.pop().unwrap_or(JsonValue::Null)
+        }
+    }
+}
+
+/// Keeps only the fields with the given paths separated by dots, lists are filtered item by item.
+pub fn select_fields(value: &JsonValue, fields: &[String]) -> JsonValue {
+    match value {
+        JsonValue::Array(v) => v.iter().map(|v| select_fields(v, fields)).collect(),
+        _ => {
+            let mut output = JsonValue::Object(Map::new());
+            for field in fields {
+                let path: Vec<&str> = field.split('.').collect();
+                let pointer = format!("/{}", path.join("/"));
+                if let Some(v) = value.pointer(&pointer) {
+                    insert(&mut output, &path, v.clone());
+                }
+            }
+            output
+        }
+    }
+}
+
+fn insert(output: &mut JsonValue, path: &[&str], value: JsonValue) {
+    if let JsonValue::Object(map) = output {
+        match path {
+            [key] => {
+                map.insert((*key).to_owned(), value);
+            }
+            [key, rest @ ..] => {
+                let entry = map
+                    .entry((*key).to_owned())
+                    .or_insert_with(|| JsonValue::Object(Map::new()));
+                insert(entry, rest, value);
+            }
+            [] => {}
+        }
+    }
+}
@@ -0,0 +1,313 @@
+use super::{
+    color, Bridge, Config, Group, Light, Modifications, Resourcelink, Rule, Scan, Scene, Schedule,
+    Sensor, User,
+};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+/// Value that is rendered with the selected output format.
+pub trait Render: Serialize {
+    /// Returns the columns of the table output that are used if no columns are selected.
+    ///
+    /// If no columns are returned, every field is a column.
+    fn columns() -> Vec<Column> {
+        Vec::new()
+    }
+}
+
+impl<T: Render> Render for Vec<T> {
+    fn columns() -> Vec<Column> {
+        T::columns()
+    }
+}
+
+/// Column of a table.
+pub struct Column {
+    pub name: String,
+    value: Box<dyn Fn(&JsonValue) -> JsonValue>,
+}
+
+impl Column {
+    /// Creates a column that computes its value from the serialized resource.
+    pub fn new<F>(name: &str, value: F) -> Self
+    where
+        F: Fn(&JsonValue) -> JsonValue + 'static,
+    {
+        Self {
+            name: name.to_owned(),
+            value: Box::new(value),
+        }
+    }
+
+    /// Creates a column that contains the field with the given path separated by dots.
+    pub fn path(name: &str, path: &str) -> Self {
+        let pointer = format!("/{}", path.replace('.', "/"));
+        Self::new(name, move |v| {
+            v.pointer(&pointer).cloned().unwrap_or(JsonValue::Null)
+        })
+    }
+
+    pub fn value(&self, resource: &JsonValue) -> JsonValue {
+        (self.value)(resource)
+    }
+}
+
+/// Returns the selected columns, names of default columns are matched before field paths.
+pub fn select(defaults: Vec<Column>, selected: &[String]) -> Vec<Column> {
+    let mut defaults: Vec<Option<Column>> = defaults.into_iter().map(Some).collect();
+    selected
+        .iter()
+        .map(|name| {
+            let index = defaults.iter().position(|v| match v {
+                Some(v) => v.name.eq_ignore_ascii_case(name),
+                None => false,
+            });
+            match index.and_then(|v| defaults[v].take()) {
+                Some(v) => v,
+                None => Column::path(name, name),
+            }
+        })
+        .collect()
+}
+
+/// Renders a value as table.
+///
+/// Lists are rendered with one row per item. A single object is rendered with one row per field,
+/// unless columns are selected.
+pub fn render(value: &JsonValue, columns: Vec<Column>, selected: bool) -> String {
+    match value {
+        JsonValue::Array(items) => {
+            let columns = if columns.is_empty() {
+                let mut names: Vec<String> = Vec::new();
+                for (key, _) in items.iter().flat_map(flatten) {
+                    if !names.contains(&key) {
+                        names.push(key);
+                    }
+                }
+                names.iter().map(|v| Column::path(v, v)).collect()
+            } else {
+                columns
+            };
+            rows(&columns, items)
+        }
+        JsonValue::Object(_) if selected => rows(&columns, std::slice::from_ref(value)),
+        JsonValue::Object(_) => {
+            let cells = flatten(value)
+                .into_iter()
+                .map(|(key, value)| vec![key, cell(&value)])
+                .collect();
+            table(vec!["KEY".to_owned(), "VALUE".to_owned()], cells)
+        }
+        _ => format!("{}\n", cell(value)),
+    }
+}
+
+fn rows(columns: &[Column], items: &[JsonValue]) -> String {
+    let header = columns.iter().map(|v| v.name.to_uppercase()).collect();
+    let cells = items
+        .iter()
+        .map(|item| columns.iter().map(|v| cell(&v.value(item))).collect())
+        .collect();
+    table(header, cells)
+}
+
+/// Flattens nested objects into a list of keys separated with dots and their values.
+pub fn flatten(value: &JsonValue) -> Vec<(String, JsonValue)> {
+    fn inner(prefix: &str, value: &JsonValue, output: &mut Vec<(String, JsonValue)>) {
+        match value {
+            JsonValue::Object(v) if !v.is_empty() => {
+                for (key, value) in v {
+                    let key = match prefix {
+                        "" => key.clone(),
+                        _ => format!("{}.{}", prefix, key),
+                    };
+                    inner(&key, value, output);
+                }
+            }
+            _ => output.push((prefix.to_owned(), value.clone())),
+        }
+    }
+    let mut output = Vec::new();
+    inner("", value, &mut output);
+    output
+}
+
+/// Returns the text of a value inside a table or plain output.
+pub fn cell(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => "-".to_owned(),
+        JsonValue::String(v) => v.clone(),
+        JsonValue::Array(v) if v.iter().all(|v| !v.is_object() && !v.is_array()) => {
+            v.iter().map(cell).collect::<Vec<String>>().join(",")
+        }
+        _ => value.to_string(),
+    }
+}
+
+/// Minimum width of a column that is truncated to fit into the terminal.
+const MIN_COLUMN_WIDTH: usize = 6;
+
+/// Renders a table with aligned columns.
+///
+/// If the output is a terminal, the widest columns are truncated until the table fits into the
+/// width of the terminal.
+pub fn table(header: Vec<String>, rows: Vec<Vec<String>>) -> String {
+    let mut widths: Vec<usize> = header.iter().map(|v| v.chars().count()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    if let Some((terminal_size::Width(terminal_width), _)) = terminal_size::terminal_size() {
+        let separators = widths.len().saturating_sub(1) * 2;
+        let terminal_width = (terminal_width as usize).saturating_sub(separators);
+        while widths.iter().sum::<usize>() > terminal_width {
+            let widest = match widths.iter_mut().max() {
+                Some(v) if *v > MIN_COLUMN_WIDTH => v,
+                _ => break,
+            };
+            *widest -= 1;
+        }
+    }
+    let mut output = String::new();
+    for row in std::iter::once(&header).chain(rows.iter()) {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{:width$}", truncate(cell, *width), width = width))
+            .collect();
+        output.push_str(cells.join("  ").trim_end());
+        output.push('\n');
+    }
+    output
+}
+
+fn truncate(value: &str, width: usize) -> String {
+    if value.chars().count() <= width {
+        return value.to_owned();
+    }
+    let mut value: String = value.chars().take(width.saturating_sub(1)).collect();
+    value.push('…');
+    value
+}
+
+fn brightness_percentage(value: &JsonValue) -> JsonValue {
+    match value.as_u64() {
+        Some(v) => format!("{}%", color::brightness_percentage(v as u8).round()).into(),
+        None => JsonValue::Null,
+    }
+}
+
+fn light_color(value: &JsonValue) -> JsonValue {
+    let state = &value["state"];
+    match state["color_mode"].as_str() {
+        Some("ColorTemperature") => match state["color_temperature"].as_u64() {
+            Some(v) => match color::kelvin(v as u16) {
+                Some(v) => format!("{}K", v).into(),
+                None => JsonValue::Null,
+            },
+            None => JsonValue::Null,
+        },
+        Some("HueAndSaturation") => match (state["hue"].as_u64(), state["saturation"].as_u64()) {
+            (Some(hue), Some(saturation)) => format!(
+                "hue {}° sat {}%",
+                color::hue_degrees(hue as u16).round(),
+                color::saturation_percentage(saturation as u8).round()
+            )
+            .into(),
+            _ => JsonValue::Null,
+        },
+        Some("ColorSpaceCoordinates") => match &state["color_space_coordinates"] {
+            JsonValue::Array(v) if v.len() == 2 => format!(
+                "xy {:.3},{:.3}",
+                v[0].as_f64().unwrap_or_default(),
+                v[1].as_f64().unwrap_or_default()
+            )
+            .into(),
+            _ => JsonValue::Null,
+        },
+        _ => JsonValue::Null,
+    }
+}
+
+fn count(value: &JsonValue) -> JsonValue {
+    match value {
+        JsonValue::Array(v) => v.len().into(),
+        _ => JsonValue::Null,
+    }
+}
+
+impl Render for Light {
+    fn columns() -> Vec<Column> {
+        vec![
+            Column::path("id", "id"),
+            Column::path("name", "name"),
+            Column::path("on", "state.on"),
+            Column::new("brightness", |v| {
+                brightness_percentage(&v["state"]["brightness"])
+            }),
+            Column::new("color", light_color),
+            Column::path("reachable", "state.reachable"),
+        ]
+    }
+}
+
+impl Render for Group {
+    fn columns() -> Vec<Column> {
+        vec![
+            Column::path("id", "id"),
+            Column::path("name", "name"),
+            Column::path("kind", "kind"),
+            Column::path("class", "class"),
+            Column::new("lights", |v| count(&v["lights"])),
+            Column::path("any_on", "state.any_on"),
+            Column::path("all_on", "state.all_on"),
+        ]
+    }
+}
+
+impl Render for Scene {
+    fn columns() -> Vec<Column> {
+        vec![
+            Column::path("id", "id"),
+            Column::path("name", "name"),
+            Column::path("kind", "kind"),
+            Column::path("group", "group"),
+            Column::new("lights", |v| count(&v["lights"])),
+            Column::path("last_update", "last_update"),
+        ]
+    }
+}
+
+impl Render for Sensor {
+    fn columns() -> Vec<Column> {
+        vec![
+            Column::path("id", "id"),
+            Column::path("name", "name"),
+            Column::path("type", "type_name"),
+            Column::path("on", "config.on"),
+            Column::path("reachable", "config.reachable"),
+            Column::path("battery", "config.battery"),
+            Column::path("last_updated", "state.last_updated"),
+        ]
+    }
+}
+
+impl Render for Modifications {
+    fn columns() -> Vec<Column> {
+        vec![
+            Column::path("path", "path"),
+            Column::path("value", "value"),
+            Column::path("success", "success"),
+            Column::path("error", "error.description"),
+        ]
+    }
+}
+
+impl Render for Bridge {}
+impl Render for Config {}
+impl Render for Resourcelink {}
+impl Render for Rule {}
+impl Render for Scan {}
+impl Render for Schedule {}
+impl Render for User {}
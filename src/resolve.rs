@@ -0,0 +1,224 @@
+use crate::{api, cache};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Type of a resource that can be addressed by its name.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Resource {
+    Light,
+    Group,
+    Scene,
+    Sensor,
+    Schedule,
+    Rule,
+    Resourcelink,
+}
+
+impl Resource {
+    /// Returns whether the identifiers of the resource are always numeric.
+    fn has_numeric_ids(self) -> bool {
+        self != Self::Scene
+    }
+
+    /// Returns whether the value is an identifier that does not need to be resolved.
+    fn is_id(self, value: &str) -> bool {
+        self.has_numeric_ids() && value.chars().all(|c| c.is_ascii_digit())
+    }
+}
+
+impl fmt::Display for Resource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let name = match self {
+            Self::Light => "light",
+            Self::Group => "group",
+            Self::Scene => "scene",
+            Self::Sensor => "sensor",
+            Self::Schedule => "schedule",
+            Self::Rule => "rule",
+            Self::Resourcelink => "resourcelink",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Identifier and name of a resource.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Candidate {
+    pub id: String,
+    pub name: String,
+}
+
+impl Candidate {
+    fn new(id: String, name: String) -> Self {
+        Self { id, name }
+    }
+}
+
+/// Returns the identifiers and names of all resources of the given type.
+///
+/// The resources are taken from the cache if it contains them, otherwise they are requested from
+/// the bridge.
+pub fn candidates(bridge: &api::Bridge, resource: Resource) -> Result<Vec<Candidate>, Error> {
+    cache::candidates(resource, || request_candidates(bridge, resource))
+}
+
+/// Requests the identifiers and names of all resources of the given type from the bridge.
+fn request_candidates(bridge: &api::Bridge, resource: Resource) -> Result<Vec<Candidate>, Error> {
+    let candidates = match resource {
+        Resource::Light => bridge.get_all_lights().map(|v| {
+            v.into_iter()
+                .map(|v| Candidate::new(v.id, v.name))
+                .collect()
+        }),
+        Resource::Group => bridge.get_all_groups().map(|v| {
+            v.into_iter()
+                .map(|v| Candidate::new(v.id, v.name))
+                .collect()
+        }),
+        Resource::Scene => bridge.get_all_scenes().map(|v| {
+            v.into_iter()
+                .map(|v| Candidate::new(v.id, v.name))
+                .collect()
+        }),
+        Resource::Sensor => bridge.get_all_sensors().map(|v| {
+            v.into_iter()
+                .map(|v| Candidate::new(v.id, v.name))
+                .collect()
+        }),
+        Resource::Schedule => bridge.get_all_schedules().map(|v| {
+            v.into_iter()
+                .map(|v| Candidate::new(v.id, v.name))
+                .collect()
+        }),
+        Resource::Rule => bridge.get_all_rules().map(|v| {
+            v.into_iter()
+                .map(|v| Candidate::new(v.id, v.name))
+                .collect()
+        }),
+        Resource::Resourcelink => bridge.get_all_resourcelinks().map(|v| {
+            v.into_iter()
+                .map(|v| Candidate::new(v.id, v.name))
+                .collect()
+        }),
+    };
+    candidates.map_err(Error::Bridge)
+}
+
+/// Returns the identifiers of resources that are given by identifier or name.
+///
+/// The resources are only requested from the bridge if a value is not a numeric identifier.
+pub fn ids(
+    bridge: &api::Bridge,
+    resource: Resource,
+    values: &[String],
+) -> Result<Vec<String>, Error> {
+    if values.iter().all(|v| resource.is_id(v)) {
+        return Ok(values.to_vec());
+    }
+    let candidates = candidates(bridge, resource)?;
+    values
+        .iter()
+        .map(|v| find(&candidates, resource, v))
+        .collect()
+}
+
+/// Returns the identifier of a resource that is given by identifier or name.
+pub fn id(bridge: &api::Bridge, resource: Resource, value: &str) -> Result<String, Error> {
+    ids(bridge, resource, &[value.to_owned()]).map(|mut v| v.remove(0))
+}
+
+/// Returns the identifier of a resource that is given by identifier, exact name or
+/// case-insensitive name.
+///
+/// This is used by commands that delete resources, so that a part of a name can not select an
+/// unrelated resource.
+pub fn exact_id(bridge: &api::Bridge, resource: Resource, value: &str) -> Result<String, Error> {
+    if resource.is_id(value) {
+        return Ok(value.to_owned());
+    }
+    find_with(&candidates(bridge, resource)?, resource, value, true)
+}
+
+/// Finds the resource with the given identifier or name.
+///
+/// Names are matched exactly, case-insensitively, by prefix, by substring and finally by
+/// subsequence, the first of these that matches resources must match a single resource.
+pub fn find(candidates: &[Candidate], resource: Resource, value: &str) -> Result<String, Error> {
+    find_with(candidates, resource, value, false)
+}
+
+/// Finds the resource with the given identifier or name, if `exact` is set names are only matched
+/// exactly and case-insensitively.
+fn find_with(
+    candidates: &[Candidate],
+    resource: Resource,
+    value: &str,
+    exact: bool,
+) -> Result<String, Error> {
+    if let Some(v) = candidates.iter().find(|v| v.id == value) {
+        return Ok(v.id.clone());
+    }
+    let value_lowercase = value.to_lowercase();
+    let is_subsequence = |name: &str| {
+        let mut chars = name.chars();
+        value_lowercase.chars().all(|c| chars.any(|v| v == c))
+    };
+    let matchers: [&dyn Fn(&str) -> bool; 5] = [
+        &|name| name == value,
+        &|name| name.to_lowercase() == value_lowercase,
+        &|name| name.to_lowercase().starts_with(&value_lowercase),
+        &|name| name.to_lowercase().contains(&value_lowercase),
+        &|name| is_subsequence(&name.to_lowercase()),
+    ];
+    let matchers = if exact { &matchers[..2] } else { &matchers[..] };
+    for matcher in matchers {
+        let matches: Vec<&Candidate> = candidates.iter().filter(|v| matcher(&v.name)).collect();
+        match matches.as_slice() {
+            [] => continue,
+            [v] => return Ok(v.id.clone()),
+            _ => {
+                return Err(Error::Ambiguous(
+                    resource,
+                    value.to_owned(),
+                    matches.into_iter().cloned().collect(),
+                ))
+            }
+        }
+    }
+    Err(Error::NotFound(resource, value.to_owned()))
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Bridge(api::Error),
+    NotFound(Resource, String),
+    Ambiguous(Resource, String, Vec<Candidate>),
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Self::Bridge(e) => write!(f, "{}", e),
+            Self::NotFound(resource, value) => write!(
+                f,
+                "No {} with the identifier or name '{}' exists",
+                resource, value
+            ),
+            Self::Ambiguous(resource, value, candidates) => {
+                let candidates: Vec<String> = candidates
+                    .iter()
+                    .map(|v| format!("{} ({})", v.id, v.name))
+                    .collect();
+                write!(
+                    f,
+                    "The name '{}' matches multiple {}s: {}",
+                    value,
+                    resource,
+                    candidates.join(", ")
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,305 @@
+use crate::{api, output, resolve};
+use huelib::resource::group::{CreatableKind, Kind as GroupKind};
+use serde_json::Value as JsonValue;
+use std::{collections::HashSet, fmt};
+
+/// Expression that selects lights or groups.
+///
+/// An expression is a list of terms separated by `,` (union), a term is a list of atoms separated
+/// by `&` (intersection). An atom has the form `<key>:<value>` with the keys `room`, `zone`,
+/// `group`, `light`, `type`, `reachable` and `on`, values that contain separators must be quoted,
+/// e.g. `room:Kitchen & reachable:true, type:"Extended color light"`. The value of a `light`
+/// atom is either an identifier or name or a filter like `name~lamp`.
+#[derive(Debug)]
+pub struct Expression {
+    terms: Vec<Vec<Atom>>,
+}
+
+#[derive(Debug)]
+enum Atom {
+    Group(GroupType, String),
+    Light(String),
+    LightFilter(String, output::Filter),
+    Type(String),
+    Reachable(bool),
+    On(bool),
+}
+
+impl fmt::Display for Atom {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Self::Group(GroupType::Room, v) => write!(f, "room:{}", v),
+            Self::Group(GroupType::Zone, v) => write!(f, "zone:{}", v),
+            Self::Group(GroupType::Any, v) => write!(f, "group:{}", v),
+            Self::Light(v) => write!(f, "light:{}", v),
+            Self::LightFilter(v, _) => write!(f, "light:{}", v),
+            Self::Type(v) => write!(f, "type:{}", v),
+            Self::Reachable(v) => write!(f, "reachable:{}", v),
+            Self::On(v) => write!(f, "on:{}", v),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum GroupType {
+    Room,
+    Zone,
+    Any,
+}
+
+/// Keys of the atoms of an expression.
+const KEYS: [&str; 7] = ["room", "zone", "group", "light", "type", "reachable", "on"];
+
+/// Returns whether a target of a subcommand is an expression instead of an identifier or name.
+///
+/// A value is an expression if one of its atoms starts with a known key, so names that contain
+/// `:` are still names. Names that start with a key are selected with a quoted value instead,
+/// e.g. `light:"Room: Desk"`.
+pub fn is_expression(s: &str) -> bool {
+    split(s, ',')
+        .iter()
+        .flat_map(|term| split(term, '&'))
+        .any(|atom| match atom.find(':') {
+            Some(v) => KEYS.contains(&atom[..v].trim().to_lowercase().as_str()),
+            None => false,
+        })
+}
+
+impl Expression {
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        let terms = split(s, ',')
+            .iter()
+            .map(|term| split(term, '&').iter().map(|v| parse_atom(v)).collect())
+            .collect::<Result<Vec<Vec<Atom>>, Error>>()?;
+        Ok(Self { terms })
+    }
+
+    /// Returns the identifiers of the selected lights in the order of the bridge.
+    ///
+    /// If `explain` is set, the lights that are matched by every atom and term are printed to
+    /// stderr.
+    pub fn lights(&self, context: &Context, explain: bool) -> Result<Vec<String>, Error> {
+        let all: Vec<&str> = context.lights.iter().map(|v| v.id.as_str()).collect();
+        self.evaluate(&all, explain, |atom| context.match_lights(atom))
+    }
+
+    /// Returns the identifiers of the selected groups in the order of the bridge.
+    pub fn groups(&self, context: &Context, explain: bool) -> Result<Vec<String>, Error> {
+        let all: Vec<&str> = context.groups.iter().map(|v| v.id.as_str()).collect();
+        self.evaluate(&all, explain, |atom| context.match_groups(atom))
+    }
+
+    fn evaluate<F>(&self, all: &[&str], explain: bool, f: F) -> Result<Vec<String>, Error>
+    where
+        F: Fn(&Atom) -> Result<HashSet<String>, Error>,
+    {
+        let report = |name: &str, ids: &HashSet<String>| {
+            if explain {
+                let ids: Vec<&str> = all.iter().copied().filter(|v| ids.contains(*v)).collect();
+                eprintln!("{} -> {}", name, ids.join(", "));
+            }
+        };
+        let mut union = HashSet::new();
+        for term in &self.terms {
+            let mut intersection: Option<HashSet<String>> = None;
+            for atom in term {
+                let ids = f(atom)?;
+                report(&atom.to_string(), &ids);
+                intersection = Some(match intersection {
+                    Some(v) => v.intersection(&ids).cloned().collect(),
+                    None => ids,
+                });
+            }
+            let intersection = intersection.unwrap_or_default();
+            if term.len() > 1 {
+                let names: Vec<String> = term.iter().map(|v| v.to_string()).collect();
+                report(&names.join(" & "), &intersection);
+            }
+            union.extend(intersection);
+        }
+        report("result", &union);
+        Ok(all
+            .iter()
+            .filter(|v| union.contains(**v))
+            .map(|v| (*v).to_owned())
+            .collect())
+    }
+}
+
+/// Splits a string at a separator that is not inside quotes.
+fn split(s: &str, separator: char) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut quote = None;
+    for c in s.chars() {
+        match (c, quote) {
+            ('"', None) | ('\'', None) => quote = Some(c),
+            (c, Some(v)) if c == v => quote = None,
+            (c, None) if c == separator => {
+                parts.push(String::new());
+                continue;
+            }
+            _ => {}
+        }
+        if let Some(v) = parts.last_mut() {
+            v.push(c);
+        }
+    }
+    parts.into_iter().map(|v| v.trim().to_owned()).collect()
+}
+
+fn parse_atom(s: &str) -> Result<Atom, Error> {
+    let (key, value) = match s.find(':') {
+        Some(v) => (s[..v].trim().to_lowercase(), s[v + 1..].trim()),
+        None => ("light".to_owned(), s),
+    };
+    let value = value.trim_matches(|c| c == '"' || c == '\'').to_owned();
+    if value.is_empty() {
+        return Err(Error::Parse(format!("Missing value in '{}'", s)));
+    }
+    let parse_bool = |v: &str| match v.to_lowercase().as_ref() {
+        "true" | "yes" => Ok(true),
+        "false" | "no" => Ok(false),
+        _ => Err(Error::Parse(format!("Invalid boolean in '{}'", s))),
+    };
+    let is_filter = ["=", "~"].iter().any(|v| value.contains(v));
+    Ok(match key.as_ref() {
+        "room" => Atom::Group(GroupType::Room, value),
+        "zone" => Atom::Group(GroupType::Zone, value),
+        "group" => Atom::Group(GroupType::Any, value),
+        "light" if is_filter => {
+            let filter = output::Filter::parse(&value).map_err(Error::Parse)?;
+            Atom::LightFilter(value, filter)
+        }
+        "light" => Atom::Light(value),
+        "type" => Atom::Type(value.to_lowercase()),
+        "reachable" => Atom::Reachable(parse_bool(&value)?),
+        "on" => Atom::On(parse_bool(&value)?),
+        _ => return Err(Error::Parse(format!("Unknown key '{}' in '{}'", key, s))),
+    })
+}
+
+/// Lights and groups that expressions are evaluated against.
+pub struct Context {
+    lights: Vec<Light>,
+    groups: Vec<huelib::resource::Group>,
+}
+
+struct Light {
+    id: String,
+    name: String,
+    kind: String,
+    reachable: bool,
+    on: Option<bool>,
+    value: JsonValue,
+}
+
+impl Context {
+    /// Requests all lights and groups from the bridge.
+    pub fn new(bridge: &api::Bridge) -> Result<Self, Error> {
+        let lights = bridge
+            .get_all_lights()
+            .map_err(Error::Bridge)?
+            .into_iter()
+            .map(|v| Light {
+                id: v.id.clone(),
+                name: v.name.clone(),
+                kind: v.kind.to_lowercase(),
+                reachable: v.state.reachable,
+                on: v.state.on,
+                value: serde_json::to_value(output::Light::from(v)).unwrap_or_default(),
+            })
+            .collect();
+        let groups = bridge.get_all_groups().map_err(Error::Bridge)?;
+        Ok(Self { lights, groups })
+    }
+
+    fn find_group(&self, kind: GroupType, name: &str) -> Result<&huelib::resource::Group, Error> {
+        let groups: Vec<&huelib::resource::Group> = self
+            .groups
+            .iter()
+            .filter(|v| match kind {
+                GroupType::Room => matches!(v.kind, GroupKind::Creatable(CreatableKind::Room)),
+                GroupType::Zone => matches!(v.kind, GroupKind::Creatable(CreatableKind::Zone)),
+                GroupType::Any => true,
+            })
+            .collect();
+        let candidates: Vec<resolve::Candidate> = groups
+            .iter()
+            .map(|v| resolve::Candidate {
+                id: v.id.clone(),
+                name: v.name.clone(),
+            })
+            .collect();
+        let id =
+            resolve::find(&candidates, resolve::Resource::Group, name).map_err(Error::Resolve)?;
+        groups
+            .into_iter()
+            .find(|v| v.id == id)
+            .ok_or_else(|| Error::Parse(format!("Group '{}' does not exist", name)))
+    }
+
+    fn match_lights(&self, atom: &Atom) -> Result<HashSet<String>, Error> {
+        let filter = |f: &dyn Fn(&Light) -> bool| {
+            self.lights
+                .iter()
+                .filter(|v| f(v))
+                .map(|v| v.id.clone())
+                .collect()
+        };
+        Ok(match atom {
+            Atom::Group(kind, name) => self
+                .find_group(*kind, name)?
+                .lights
+                .iter()
+                .cloned()
+                .collect(),
+            Atom::Light(value) => {
+                let candidates: Vec<resolve::Candidate> = self
+                    .lights
+                    .iter()
+                    .map(|v| resolve::Candidate {
+                        id: v.id.clone(),
+                        name: v.name.clone(),
+                    })
+                    .collect();
+                let id = resolve::find(&candidates, resolve::Resource::Light, value)
+                    .map_err(Error::Resolve)?;
+                std::iter::once(id).collect()
+            }
+            Atom::LightFilter(_, v) => filter(&|light| v.matches(&light.value)),
+            Atom::Type(v) => filter(&|light| light.kind.contains(v.as_str())),
+            Atom::Reachable(v) => filter(&|light| light.reachable == *v),
+            Atom::On(v) => filter(&|light| light.on == Some(*v)),
+        })
+    }
+
+    fn match_groups(&self, atom: &Atom) -> Result<HashSet<String>, Error> {
+        match atom {
+            Atom::Group(kind, name) => {
+                Ok(std::iter::once(self.find_group(*kind, name)?.id.clone()).collect())
+            }
+            _ => Err(Error::Unsupported(atom.to_string())),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Parse(String),
+    Unsupported(String),
+    Resolve(resolve::Error),
+    Bridge(api::Error),
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Self::Parse(e) => write!(f, "{}", e),
+            Self::Unsupported(e) => write!(f, "'{}' selects lights instead of groups", e),
+            Self::Resolve(e) => write!(f, "{}", e),
+            Self::Bridge(e) => write!(f, "{}", e),
+        }
+    }
+}
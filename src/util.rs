@@ -1,19 +1,254 @@
-use crate::config;
+use crate::{address::Address, api, config, discover, error, output, resolve, select};
+use std::{net::IpAddr, sync::OnceLock, time::Duration};
 
 macro_rules! exit {
     ( $description:expr ) => {{
-        eprintln!("{}", $description);
-        std::process::exit(1)
+        crate::error::Error::new(crate::error::Kind::General, &$description).exit()
     }};
     ( $description:expr, $error:expr ) => {{
-        eprintln!("{}: {}", $description, $error);
-        std::process::exit(1)
+        crate::error::Error::with_cause(&$description, &$error).exit()
     }};
 }
 
-pub fn get_bridge() -> huelib::Bridge {
+/// Removes duplicate identifiers and keeps the order of the first occurrences.
+pub fn unique(ids: Vec<String>) -> Vec<String> {
+    let mut output: Vec<String> = Vec::new();
+    for id in ids {
+        if !output.contains(&id) {
+            output.push(id);
+        }
+    }
+    output
+}
+
+/// Returns the identifiers of the lights that are selected by an expression.
+pub fn select_lights(bridge: &api::Bridge, expression: &str, explain: bool) -> Vec<String> {
+    let result = select::Expression::parse(expression).and_then(|v| {
+        let context = select::Context::new(bridge)?;
+        v.lights(&context, explain)
+    });
+    match result {
+        Ok(v) => v,
+        Err(e) => exit!(format!("Failed to select lights with '{}'", expression), e),
+    }
+}
+
+/// Returns the identifiers of the groups that are selected by an expression.
+pub fn select_groups(bridge: &api::Bridge, expression: &str, explain: bool) -> Vec<String> {
+    let result = select::Expression::parse(expression).and_then(|v| {
+        let context = select::Context::new(bridge)?;
+        v.groups(&context, explain)
+    });
+    match result {
+        Ok(v) => v,
+        Err(e) => exit!(format!("Failed to select groups with '{}'", expression), e),
+    }
+}
+
+/// Returns the identifiers of lights or groups that are given by identifier, name or selector
+/// expression.
+pub fn select_ids(
+    bridge: &api::Bridge,
+    resource: resolve::Resource,
+    values: &[String],
+    explain: bool,
+) -> Vec<String> {
+    let (expressions, names): (Vec<String>, Vec<String>) = values
+        .iter()
+        .cloned()
+        .partition(|v| select::is_expression(v));
+    let mut ids = resolve_ids(bridge, resource, &names);
+    for expression in &expressions {
+        match resource {
+            resolve::Resource::Group => ids.extend(select_groups(bridge, expression, explain)),
+            _ => ids.extend(select_lights(bridge, expression, explain)),
+        }
+    }
+    ids
+}
+
+/// Prints the results of modifying attributes.
+///
+/// If any attribute failed to be modified, huectl exits with the code of the first error.
+pub fn print_modifications(modifications: &output::Modifications) {
+    output::print(modifications);
+    if let Some(v) = modifications.error_type() {
+        std::process::exit(error::Kind::from_api_error(v).exit_code());
+    }
+}
+
+/// Time to wait for the bridge when its identity is verified or it is searched in the local
+/// network.
+const DISCOVER_TIMEOUT: Duration = Duration::from_secs(3);
+
+pub fn get_config() -> config::Config {
     match config::get() {
-        Ok(v) => huelib::Bridge::new(v.bridge_ip, &v.bridge_username),
-        Err(e) => exit!("Failed to get configuration environment variables", e),
+        Ok(v) => v,
+        Err(e) => exit!("Failed to get configuration", e),
+    }
+}
+
+pub fn get_bridge() -> api::Bridge {
+    let config = get_config();
+    get_bridge_at(&get_address(&config), &config)
+}
+
+/// Returns the bridge at an address that was returned by `get_address`.
+pub fn get_bridge_at(address: &Address, config: &config::Config) -> api::Bridge {
+    api::Bridge::new(address.clone(), &config.bridge_username)
+}
+
+/// Returns the identifier of a resource that is given by identifier or name.
+pub fn resolve_id(bridge: &api::Bridge, resource: resolve::Resource, value: &str) -> String {
+    match resolve::id(bridge, resource, value) {
+        Ok(v) => v,
+        Err(e) => exit!(format!("Failed to find {}", resource), e),
+    }
+}
+
+/// Returns the identifier of a resource that is given by identifier, exact name or
+/// case-insensitive name.
+pub fn resolve_exact_id(bridge: &api::Bridge, resource: resolve::Resource, value: &str) -> String {
+    match resolve::exact_id(bridge, resource, value) {
+        Ok(v) => v,
+        Err(e) => exit!(format!("Failed to find {}", resource), e),
+    }
+}
+
+/// Returns the identifiers of resources that are given by identifier or name.
+pub fn resolve_ids(
+    bridge: &api::Bridge,
+    resource: resolve::Resource,
+    values: &[String],
+) -> Vec<String> {
+    match resolve::ids(bridge, resource, values) {
+        Ok(v) => v,
+        Err(e) => exit!(format!("Failed to find {}s", resource), e),
+    }
+}
+
+/// Profile name, pinned bridge identifier and address of the profile that is used, if the bridge
+/// of the profile has a pinned identifier.
+static PINNED_BRIDGE: OnceLock<(String, String, Address)> = OnceLock::new();
+
+/// Returns the address of the bridge.
+pub fn get_address(config: &config::Config) -> Address {
+    match &config.profile {
+        Some(profile) if config.bridge_address_from_profile => pin_profile_bridge(profile, config),
+        _ => config.bridge_address.clone(),
+    }
+}
+
+/// Stores the identifier of the bridge in the profile if no identifier is pinned yet.
+///
+/// A pinned identifier is only verified if a request to the bridge fails, see `relocate_bridge`
+/// and `verify_bridge`.
+fn pin_profile_bridge(profile: &str, config: &config::Config) -> Address {
+    let address = config.bridge_address.clone();
+    match &config.bridge_id {
+        Some(v) => {
+            let _ = PINNED_BRIDGE.set((profile.to_owned(), v.clone(), address.clone()));
+        }
+        None => {
+            if let Ok(v) = discover::get_config(&address, DISCOVER_TIMEOUT) {
+                let _ = config::update_profile(profile, |profile| {
+                    profile.bridge_id = Some(v.bridge_id)
+                });
+            }
+        }
+    }
+    address
+}
+
+/// Address at which the pinned bridge was searched in the local network, `None` if it was not
+/// found.
+static RELOCATED_BRIDGE: OnceLock<Option<Address>> = OnceLock::new();
+
+/// Returns the address of the pinned bridge if it was found at another address than the address of
+/// the profile.
+pub fn relocated_address(address: &Address) -> Option<&'static Address> {
+    match PINNED_BRIDGE.get() {
+        Some((_, _, v)) if v == address => RELOCATED_BRIDGE.get()?.as_ref(),
+        _ => None,
+    }
+}
+
+/// Searches the pinned bridge in the local network after it was unreachable at the address of the
+/// profile, and updates the address of the profile if it is found.
+///
+/// The search is only done once per command, the new address is returned so that the failed
+/// request can be sent again.
+pub fn relocate_bridge(address: &Address) -> Option<&'static Address> {
+    let (profile, bridge_id, pinned_address) = PINNED_BRIDGE.get()?;
+    if pinned_address != address {
+        return None;
+    }
+    RELOCATED_BRIDGE
+        .get_or_init(|| {
+            eprintln!(
+                "Bridge '{}' is unreachable at '{}', searching the local network...",
+                bridge_id, address
+            );
+            let address = Address::from(find_bridge(bridge_id)?);
+            match config::update_profile(profile, |v| v.bridge_address = address.clone()) {
+                Ok(_) => eprintln!(
+                    "Updated the address of profile '{}' to '{}'",
+                    profile, address
+                ),
+                Err(e) => eprintln!(
+                    "Failed to update the address of profile '{}' to '{}': {}",
+                    profile, address, e
+                ),
+            }
+            Some(address)
+        })
+        .as_ref()
+}
+
+/// Verifies that the bridge at the address of the profile has the pinned identifier after a
+/// request failed.
+///
+/// If another bridge has the address or the bridge was not found in the local network, a hint is
+/// added to the error.
+pub fn verify_bridge(error: error::Error) -> error::Error {
+    let (_, bridge_id, address) = match PINNED_BRIDGE.get() {
+        Some(v) => v,
+        None => return error,
+    };
+    match RELOCATED_BRIDGE.get() {
+        Some(Some(_)) => return error,
+        Some(None) => {
+            return error.with_hint(format!(
+                "Bridge '{}' was not found in the local network",
+                bridge_id
+            ))
+        }
+        None => {}
+    }
+    match discover::get_config(address, DISCOVER_TIMEOUT) {
+        Ok(v) if !v.bridge_id.eq_ignore_ascii_case(bridge_id) => error.with_hint(format!(
+            "The bridge at '{}' has the identifier '{}' instead of '{}'",
+            address, v.bridge_id, bridge_id
+        )),
+        _ => error,
+    }
+}
+
+/// Searches the bridge with the given identifier in the local network.
+fn find_bridge(bridge_id: &str) -> Option<IpAddr> {
+    for method in &[discover::Method::Mdns, discover::Method::Ssdp] {
+        let ip_addresses = match discover::discover(*method, DISCOVER_TIMEOUT) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        for ip_address in ip_addresses {
+            let config = discover::get_config(&Address::from(ip_address), DISCOVER_TIMEOUT);
+            if let Ok(v) = config {
+                if v.bridge_id.eq_ignore_ascii_case(bridge_id) {
+                    return Some(ip_address);
+                }
+            }
+        }
     }
+    None
 }